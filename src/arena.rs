@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use bevy::prelude::{Component, Resource};
+
+use crate::shared_consts::PIXELS_PER_METER;
+
+/// Height of the side walls, in arena pixels.
+pub const WALL_HEIGHT: f32 = 9.0 * PIXELS_PER_METER * 1.620_689_6;
+/// Distance between the side walls.
+pub const GROUND_WIDTH: f32 = 8.0 * PIXELS_PER_METER;
+/// y of the floor; the arena is centred on the origin.
+pub const GROUND_POSITION: f32 = -0.5 * WALL_HEIGHT;
+
+/// Whether a point lies between the side walls and between the floor and the ceiling.
+pub fn inside_walls(x: f32, y: f32) -> bool {
+    x.abs() <= 0.5 * GROUND_WIDTH && (GROUND_POSITION..=GROUND_POSITION + WALL_HEIGHT).contains(&y)
+}
+
+/// What happens at the top and bottom of the arena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryMode {
+    /// Closed box: floor and ceiling walls (original behavior)
+    #[default]
+    Closed,
+    /// No floor or ceiling; balls falling out the bottom re-enter at the top with their velocity and joints
+    Wrap,
+    /// Floor with drains; balls that fall through a drain are removed
+    Recycle,
+}
+
+impl std::str::FromStr for BoundaryMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "closed" => Ok(Self::Closed),
+            "wrap" => Ok(Self::Wrap),
+            "recycle" => Ok(Self::Recycle),
+            other => Err(format!("unknown boundary mode {other:?} (expected closed, wrap or recycle)")),
+        }
+    }
+}
+
+/// What a peg does to balls touching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Component)]
+#[serde(rename_all = "snake_case")]
+pub enum PegKind {
+    /// Plain obstacle (original behavior)
+    #[default]
+    Inert,
+    /// Feeds life points to touching balls
+    Nutrient,
+    /// Drains life points from touching balls
+    Draining,
+    /// Holds touching balls with a temporary joint
+    Sticky,
+}
+
+/// Arena layout chosen at startup.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArenaConfig {
+    pub boundary: BoundaryMode,
+    /// Recycle mode: number of evenly spaced openings in the floor
+    pub drain_count: u32,
+    /// Recycle mode: width of each opening, in ball diameters
+    pub drain_width: f32,
+    /// Fractions of procedurally generated pegs given each interactive kind; the rest stay inert
+    pub nutrient_peg_fraction: f32,
+    pub draining_peg_fraction: f32,
+    pub sticky_peg_fraction: f32,
+    /// Explicit kinds by peg index (spawn order); these override the fractions
+    pub peg_kinds: BTreeMap<u32, PegKind>,
+}
+
+impl Default for ArenaConfig {
+    fn default() -> Self {
+        Self {
+            boundary: BoundaryMode::Closed,
+            drain_count: 3,
+            drain_width: 3.0,
+            nutrient_peg_fraction: 0.0,
+            draining_peg_fraction: 0.0,
+            sticky_peg_fraction: 0.0,
+            peg_kinds: BTreeMap::new(),
+        }
+    }
+}
+
+impl ArenaConfig {
+    /// Read the JSON file named by `ARENA_CONFIG` (if any), then apply
+    /// `BOUNDARY_MODE`, `DRAIN_COUNT` and `DRAIN_WIDTH` on top.
+    pub fn from_env() -> Self {
+        let mut cfg = match std::env::var("ARENA_CONFIG") {
+            Ok(path) => match std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| serde_json::from_str::<Self>(&text).map_err(|e| e.to_string()))
+                .and_then(|cfg| cfg.validate().map(|_| cfg))
+            {
+                Ok(cfg) => cfg,
+                Err(e) => {
+                    eprintln!("[warn] ARENA_CONFIG {path}: {e}; using defaults");
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        };
+        if let Ok(mode) = std::env::var("BOUNDARY_MODE") {
+            match mode.parse() {
+                Ok(mode) => cfg.boundary = mode,
+                Err(e) => eprintln!("[warn] BOUNDARY_MODE: {e}; using {:?}", cfg.boundary),
+            }
+        }
+        if let Some(n) = std::env::var("DRAIN_COUNT").ok().and_then(|s| s.parse().ok()) {
+            cfg.drain_count = n;
+        }
+        if let Some(w) = std::env::var("DRAIN_WIDTH").ok().and_then(|s| s.parse::<f32>().ok()).filter(|w| *w > 0.0) {
+            cfg.drain_width = w;
+        }
+        cfg
+    }
+
+    /// Peg fractions must each be in [0, 1] and sum to at most 1; drains must have a width.
+    pub fn validate(&self) -> Result<(), String> {
+        let fractions = [self.nutrient_peg_fraction, self.draining_peg_fraction, self.sticky_peg_fraction];
+        if fractions.iter().any(|f| !(0.0..=1.0).contains(f)) || fractions.iter().sum::<f32>() > 1.0 {
+            return Err("arena peg fractions must each be in [0, 1] and sum to at most 1".to_string());
+        }
+        if !(self.drain_width.is_finite() && self.drain_width > 0.0) {
+            return Err(format!("arena.drain_width must be > 0, got {}", self.drain_width));
+        }
+        Ok(())
+    }
+
+    /// Kind for a freshly generated peg: explicit assignment first, otherwise a roll against the fractions.
+    /// `roll` is only called when some fraction is non-zero, so inert-only layouts consume no randomness.
+    pub fn peg_kind_for(&self, index: u32, roll: impl FnOnce() -> f32) -> PegKind {
+        if let Some(kind) = self.peg_kinds.get(&index) {
+            return *kind;
+        }
+        let total = self.nutrient_peg_fraction + self.draining_peg_fraction + self.sticky_peg_fraction;
+        if total <= 0.0 {
+            return PegKind::Inert;
+        }
+        let r = roll();
+        if r < self.nutrient_peg_fraction {
+            PegKind::Nutrient
+        } else if r < self.nutrient_peg_fraction + self.draining_peg_fraction {
+            PegKind::Draining
+        } else if r < total {
+            PegKind::Sticky
+        } else {
+            PegKind::Inert
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_peg_fractions_and_drain_width() {
+        assert!(ArenaConfig::default().validate().is_ok());
+        let ok = ArenaConfig { nutrient_peg_fraction: 0.5, draining_peg_fraction: 0.25, sticky_peg_fraction: 0.25, ..Default::default() };
+        assert!(ok.validate().is_ok());
+        for bad in [
+            ArenaConfig { nutrient_peg_fraction: 0.8, sticky_peg_fraction: 0.5, ..Default::default() },
+            ArenaConfig { draining_peg_fraction: -0.1, ..Default::default() },
+            ArenaConfig { sticky_peg_fraction: f32::NAN, ..Default::default() },
+            ArenaConfig { drain_width: 0.0, ..Default::default() },
+        ] {
+            assert!(bad.validate().is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn explicit_kinds_beat_fractions_and_inert_layouts_skip_the_roll() {
+        let arena = ArenaConfig {
+            nutrient_peg_fraction: 0.2,
+            draining_peg_fraction: 0.3,
+            sticky_peg_fraction: 0.1,
+            peg_kinds: BTreeMap::from([(4, PegKind::Sticky)]),
+            ..Default::default()
+        };
+        assert_eq!(arena.peg_kind_for(4, || 0.0), PegKind::Sticky);
+        let kinds: Vec<PegKind> = [0.1, 0.3, 0.55, 0.9].into_iter().map(|r| arena.peg_kind_for(0, || r)).collect();
+        assert_eq!(kinds, vec![PegKind::Nutrient, PegKind::Draining, PegKind::Sticky, PegKind::Inert]);
+
+        let inert = ArenaConfig::default();
+        assert_eq!(inert.peg_kind_for(0, || panic!("no roll for an inert layout")), PegKind::Inert);
+    }
+}
@@ -1,12 +1,13 @@
 use rand::{rngs::StdRng, Rng};
 use std::f32::consts::PI;
 
+use bevy::ecs::system::SystemParam;
 use bevy::{
     color::Hsla,
     prelude::{
-        App, Assets, Children, Color, Commands, Component,
-        Entity, EventReader, GlobalTransform, Plugin, Query, Res, ResMut, Resource, Time, Timer, TimerMode,
-        Transform, Update, Vec2, With,
+        App, Assets, Bundle, Children, Color, Commands, Component,
+        Entity, Handle, EventReader, EventWriter, FixedFirst, FixedUpdate, GlobalTransform, IntoScheduleConfigs, Plugin, Query, Res,
+        ResMut, Resource, Time, Timer, TimerMode, Transform, Update, Vec2, With,
     },
     render::{prelude::Mesh2d},
     render::mesh::Mesh,
@@ -14,19 +15,24 @@ use bevy::{
     sprite::{ColorMaterial, MeshMaterial2d},
 };
 use bevy_rapier2d::prelude::{
-    ActiveEvents, Collider, ColliderMassProperties, ContactForceEvent, Friction,
-    ImpulseJoint as BevyImpulseJoint, QueryFilter, RapierContext, RapierImpulseJointHandle,
+    ActiveEvents, Collider, ColliderMassProperties, ContactForceEvent, ContactForceEventThreshold, Friction,
+    ImpulseJoint as BevyImpulseJoint, PhysicsSet, QueryFilter, RapierContext, RapierImpulseJointHandle,
     Restitution, RevoluteJointBuilder, RigidBody, Velocity,
 };
 
 use crate::{
-    setup::{RngResource, GROUND_WIDTH, WALL_HEIGHT, WALL_THICKNESS},
+    arena::{ArenaConfig, BoundaryMode, GROUND_POSITION, GROUND_WIDTH, WALL_HEIGHT},
+    ball_systems::BallId,
+    balls::BallIds,
+    events::{BallBorn, BallDied, Bitten, DeathCause, JointBroken, JointFormed},
+    setup::{ContactForceThreshold, RngResource, WALL_THICKNESS},
     shared_consts::PIXELS_PER_METER,
+    sim::SimClock,
     markers::{update_force_markers, ForceMarker},
 };
 
 pub const BALL_RADIUS: f32 = 0.05 * PIXELS_PER_METER;
-const MAX_LIFE_POINTS: u32 = u32::MAX / 2_u32.pow(32 - 10);
+pub const MAX_LIFE_POINTS: u32 = u32::MAX / 2_u32.pow(32 - 10);
 const COLOR_SATURATION_SCALE_FACTOR: f32 = 10.0;
 const COLOR_SATURATION_MINIMUM: f32 = 0.10;
 
@@ -97,6 +103,10 @@ impl Ball {
     pub fn get_color(&self) -> Color {
         Color::hsl(self.get_hue(), self.get_saturation(), 0.5)
     }
+    /// Low enough that the next life tick removes it.
+    fn is_dying(&self) -> bool {
+        self.life_points <= 9
+    }
     fn is_friendly_with(&self, other: Self) -> bool {
         let scent_1 = self.genome_friendly_scent;
         let scent_2 = other.genome_friendly_scent;
@@ -118,10 +128,75 @@ struct ReproduceBallsTimer(pub Timer);
 #[derive(Resource)]
 struct BallAndJointLoopTimer(pub Timer);
 #[derive(Resource, Default)]
-struct FrameCounter{ frame:u64 }
+pub struct FrameCounter{ pub frame:u64 }
 
 #[derive(Component)]
-struct JointBorn{ frame:u64 }
+pub struct JointBorn{ pub frame:u64 }
+
+// Impulse a joint has absorbed over its lifetime; drives impulse fatigue
+#[derive(Component, Default)]
+pub(crate) struct JointWear{ accumulated_impulse: f32 }
+
+#[derive(Resource)]
+struct JointStatsLogTimer(pub Timer);
+
+const JOINT_AGE_HISTOGRAM_BUCKETS: usize = 16;
+
+/// Lifetime statistics of joints that broke, with ages in frames.
+/// Histogram bucket `i` counts ages in `[2^i - 1, 2^(i+1) - 1)`; the last bucket is open-ended.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct JointLifetimeStats {
+    pub breaks: u64,
+    pub total_age_frames: u64,
+    pub age_histogram: [u64; JOINT_AGE_HISTOGRAM_BUCKETS],
+}
+
+impl JointLifetimeStats {
+    pub fn record_break(&mut self, age_frames: u64) {
+        self.breaks += 1;
+        self.total_age_frames = self.total_age_frames.saturating_add(age_frames);
+        let bucket = (64 - (age_frames + 1).leading_zeros() as usize - 1).min(JOINT_AGE_HISTOGRAM_BUCKETS - 1);
+        self.age_histogram[bucket] += 1;
+    }
+    pub fn mean_age_frames(&self) -> f64 {
+        if self.breaks == 0 { 0.0 } else { self.total_age_frames as f64 / self.breaks as f64 }
+    }
+}
+
+// Fraction of the break threshold a joint still holds after aging and absorbing impulse
+fn joint_strength(age_frames: u64, accumulated_impulse: f32, tuning: &crate::tuning::PhysicsTuning) -> f32 {
+    let worn = 1.0
+        - (age_frames as f32) * tuning.joint_age_fatigue_rate
+        - accumulated_impulse * tuning.joint_impulse_fatigue_rate;
+    worn.clamp(tuning.joint_min_strength.clamp(0.0, 1.0), 1.0)
+}
+
+fn advance_frame_counter(mut frame_counter: ResMut<FrameCounter>) {
+    frame_counter.frame += 1;
+}
+
+// Mirror frame and simulated time for the HTTP server (history stamps, schedules)
+fn publish_sim_clock(frame_counter: Res<FrameCounter>, time: Res<Time>, clock: Option<Res<SimClock>>) {
+    if let Some(clock) = clock {
+        clock.set(frame_counter.frame, time.elapsed_secs_f64());
+    }
+}
+
+fn log_joint_lifetime_stats(
+    time: Res<Time>,
+    mut timer: ResMut<JointStatsLogTimer>,
+    stats: Res<JointLifetimeStats>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() || stats.breaks == 0 {
+        return;
+    }
+    eprintln!(
+        "[diag] joint_lifetime breaks={} mean_age_frames={:.1} age_histogram={:?}",
+        stats.breaks,
+        stats.mean_age_frames(),
+        stats.age_histogram,
+    );
+}
 
 
 
@@ -136,6 +211,8 @@ fn update_life_points(
     mut rng_resource: ResMut<RngResource>,
     _q_velocities: Query<&Velocity>, // retained for future use
     tuning: Res<crate::tuning::PhysicsTuning>,
+    q_ids: Query<(&BallId, &Transform)>,
+    mut died: EventWriter<BallDied>,
 ) {
     if !timer.0.tick(time.delta()).just_finished() {
         return;
@@ -146,7 +223,10 @@ fn update_life_points(
         if ball.age > ball.genome_max_age {
             ball.life_points = ball.life_points.saturating_sub(tuning.survival_cost_per_tick);
         }
-        if ball.life_points <= 9 {
+        if ball.is_dying() {
+            let cause = if ball.age > ball.genome_max_age { DeathCause::OldAge } else { DeathCause::Starved };
+            let (id, position) = q_ids.get(entity).map_or((None, Vec2::ZERO), |(id, tf)| (Some(id.0), tf.translation.truncate()));
+            died.write(BallDied { id, cause, position, age: ball.age });
             commands.entity(entity).despawn();
         }
         let Some(color_material) = color_materials.get_mut(color_handle) else { continue };
@@ -156,6 +236,14 @@ fn update_life_points(
     let rng = &mut rng_resource.rng;
 
     for (joint, parent) in q_impulse_joints.iter() {
+        if tuning.joint_upkeep_cost_per_tick > 0 {
+            if let Ok([(_, mut parent_ball, _), (_, mut child_ball, _)]) =
+                q_balls_and_colors.get_many_mut([parent.parent(), joint.parent])
+            {
+                parent_ball.life_points = parent_ball.life_points.saturating_sub(tuning.joint_upkeep_cost_per_tick);
+                child_ball.life_points = child_ball.life_points.saturating_sub(tuning.joint_upkeep_cost_per_tick);
+            }
+        }
         let [(mut parent_ball, parent_color_handle), (mut child_ball, child_color_handle)] =
             match q_balls_and_colors.get_many_mut([parent.parent(), joint.parent]) {
                 Ok(
@@ -282,6 +370,10 @@ fn reproduce_balls(
     )>,
     q_bevy_impulse_joints: Query<&BevyImpulseJoint>,
     tuning: Res<crate::tuning::PhysicsTuning>,
+    contact_force_threshold: Res<ContactForceThreshold>,
+    ids: Res<BallIds>,
+    q_ids: Query<&BallId>,
+    mut born: EventWriter<BallBorn>,
 ) {
     if !timer.0.tick(time.delta()).just_finished() {
         return;
@@ -361,28 +453,67 @@ fn reproduce_balls(
         eprintln!("[diag] reproduce spawn at ({:.1},{:.1})", new_ball_x, new_ball_y);
 
         // Spawn physics entity with render components combined (no BallRender child)
-        let initial = child_ball.get_color();
-        let _entity = commands
-            .spawn((
+        let id = ids.next();
+        born.write(BallBorn {
+            id,
+            parent: q_ids.get(_parent_entity).ok().map(|p| p.0),
+            position: Vec2::new(new_ball_x, new_ball_y),
+            life_points: child_ball.life_points,
+        });
+        commands.spawn((
+            ball_bundle(
                 child_ball,
-                RigidBody::Dynamic,
-                Collider::ball(radius),
-                ColliderMassProperties::Density(0.001),
-                Friction::coefficient(0.7),
-                Velocity {
-                    linvel: linearvelocity,
-                    angvel: 0.0,
-                },
-                ActiveEvents::CONTACT_FORCE_EVENTS,
-                // If available, per-collider event threshold could be inserted here
-                // ContactForceEventThreshold(threshold_force),
-                Restitution::new(0.1),
-                Transform::from_xyz(new_ball_x, new_ball_y, 0.0),
-                GlobalTransform::default(),
-                Mesh2d(_mesh_assets.ball_circle.clone()),
-                MeshMaterial2d(color_materials.add(ColorMaterial::from(initial))),
-            ))
-            .id();
+                Vec2::new(new_ball_x, new_ball_y),
+                linearvelocity,
+                _mesh_assets.ball_circle.clone(),
+                &mut color_materials,
+                contact_force_threshold.0,
+            ),
+            BallId(id),
+        ));
+    }
+}
+
+/// Physics and render components of a live ball. Every spawn path (seeding, budding, the HTTP API) uses this.
+pub fn ball_bundle(
+    ball: Ball,
+    position: Vec2,
+    linvel: Vec2,
+    mesh: Handle<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    contact_force_threshold: f32,
+) -> impl Bundle {
+    let initial = ball.get_color();
+    (
+        ball,
+        RigidBody::Dynamic,
+        Collider::ball(BALL_RADIUS),
+        ColliderMassProperties::Density(0.001),
+        Friction::coefficient(0.7),
+        Velocity { linvel, angvel: 0.0 },
+        ActiveEvents::CONTACT_FORCE_EVENTS,
+        ContactForceEventThreshold(contact_force_threshold),
+        Restitution::new(0.1),
+        Transform::from_xyz(position.x, position.y, 0.0),
+        GlobalTransform::default(),
+        Mesh2d(mesh),
+        MeshMaterial2d(materials.add(ColorMaterial::from(initial))),
+    )
+}
+
+/// A newborn ball with full life and a genome drawn from the tuning's genome ranges.
+pub fn random_ball(rng: &mut StdRng, t: &crate::tuning::PhysicsTuning) -> Ball {
+    let scent_r = t.genome_friendly_scent_range;
+    Ball {
+        age: 0,
+        life_points: MAX_LIFE_POINTS,
+        genome_max_age: rng.gen_range(t.genome_max_age_min, t.genome_max_age_max),
+        genome_relative_reproduction_rate: rng.gen_range(t.genome_reproduction_rate_min, t.genome_reproduction_rate_max),
+        genome_bite_size: rng.gen_range(t.genome_bite_size_min, t.genome_bite_size_max),
+        genome_life_points_safe_to_reproduce: rng.gen_range(t.genome_safe_reproduction_points_min, t.genome_safe_reproduction_points_max),
+        genome_energy_share_with_children: rng.gen_range(t.genome_energy_share_min, t.genome_energy_share_max),
+        genome_friendly_scent: Vec2::new(rng.gen_range(-scent_r, scent_r), rng.gen_range(-scent_r, scent_r)),
+        genome_friendly_distance: rng.gen_range(t.genome_friendly_distance_min, t.genome_friendly_distance_max),
     }
 }
 
@@ -418,6 +549,9 @@ fn add_balls(
     mut materials: ResMut<Assets<ColorMaterial>>,
     _q_balls: Query<Entity, With<Ball>>,
     tuning: Res<crate::tuning::PhysicsTuning>,
+    contact_force_threshold: Res<ContactForceThreshold>,
+    ids: Res<BallIds>,
+    mut born: EventWriter<BallBorn>,
 ) {
     if !timer.0.tick(time.delta()).just_finished() {
         return;
@@ -429,26 +563,11 @@ fn add_balls(
         rng.gen_range(MIN_LINEAR_VELOCITY.y, MAX_LINEAR_VELOCITY.y),
     );
 
-    let t = tuning.into_inner();
-    let scent_r = t.genome_friendly_scent_range;
-    let ball = Ball {
-        age: 0,
-        life_points: MAX_LIFE_POINTS,
-        genome_max_age: rng.gen_range(t.genome_max_age_min, t.genome_max_age_max),
-        genome_relative_reproduction_rate: rng.gen_range(t.genome_reproduction_rate_min, t.genome_reproduction_rate_max),
-        genome_bite_size: rng.gen_range(t.genome_bite_size_min, t.genome_bite_size_max),
-        genome_life_points_safe_to_reproduce: rng.gen_range(t.genome_safe_reproduction_points_min, t.genome_safe_reproduction_points_max),
-        genome_energy_share_with_children: rng.gen_range(t.genome_energy_share_min, t.genome_energy_share_max),
-        genome_friendly_scent: Vec2::new(rng.gen_range(-scent_r, scent_r), rng.gen_range(-scent_r, scent_r)),
-        genome_friendly_distance: rng.gen_range(t.genome_friendly_distance_min, t.genome_friendly_distance_max),
-    };
+    let ball = random_ball(rng, &tuning);
     // Force bright green for debugging visibility parity with test ball
     // TEMP DEBUG: neon magenta to maximize visibility
     let _color_material: ColorMaterial = ColorMaterial::from(Color::hsl(300.0, 1.0, 0.5));
 
-    // Collider for the spawned rigid body
-    let circle_collider = Collider::ball(BALL_RADIUS);
-
     let Ok(ctx) = rapier.single() else { return; };
 
     // Spawn in a mid band that is clearly within the playfield
@@ -471,28 +590,19 @@ fn add_balls(
     // update our timer with the time elapsed since the last update
     // if that caused the timer to finish, we say hello to everyone
     // Spawn single entity with both physics and render components
-    let initial = ball.get_color();
-    let _entity = commands
-        .spawn((
+    let id = ids.next();
+    born.write(BallBorn { id, parent: None, position: Vec2::new(x, y), life_points: ball.life_points });
+    commands.spawn((
+        ball_bundle(
             ball,
-            RigidBody::Dynamic,
-            circle_collider,
-            ColliderMassProperties::Density(0.001),
-            Friction::coefficient(0.7),
-            Velocity {
-                linvel: linearvelocity * PIXELS_PER_METER,
-                angvel: 0.0,
-            },
-            ActiveEvents::CONTACT_FORCE_EVENTS,
-            // If available, per-collider event threshold could be inserted here
-            // ContactForceEventThreshold(threshold_force),
-            Restitution::new(0.1),
-            Transform::from_xyz(x, y, 0.0),
-            GlobalTransform::default(),
-            Mesh2d(mesh_assets.ball_circle.clone()),
-            MeshMaterial2d(materials.add(ColorMaterial::from(initial))),
-        ))
-        .id();
+            Vec2::new(x, y),
+            linearvelocity * PIXELS_PER_METER,
+            mesh_assets.ball_circle.clone(),
+            &mut materials,
+            contact_force_threshold.0,
+        ),
+        BallId(id),
+    ));
 }
 
 const MAX_JOINTS: usize = 10;
@@ -537,6 +647,14 @@ fn already_has_max_pairwise_joints(
     false
 }
 
+// Event side of `contacts`, grouped to stay within Bevy's limit of 16 system parameters
+#[derive(SystemParam)]
+struct ContactEvents<'w, 's> {
+    ids: Query<'w, 's, &'static BallId>,
+    bitten: EventWriter<'w, Bitten>,
+    formed: EventWriter<'w, JointFormed>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn contacts(
     mut commands: Commands,
@@ -556,8 +674,14 @@ fn contacts(
     q_existing_markers: Query<(&Transform, &ForceMarker)>,
     frame_counter: ResMut<FrameCounter>,
     tuning: Res<crate::tuning::PhysicsTuning>,
+    mut events: ContactEvents,
 ) {
     let Ok(ctx) = rapier.single() else { return; };
+    let id_of = |e: Entity| events.ids.get(e).ok().map(|id| id.0);
+    let midpoint = |a: Entity, b: Entity| match q_global_transforms.get_many([a, b]) {
+        Ok([t1, t2]) => (t1.translation().truncate() + t2.translation().truncate()) * 0.5,
+        Err(_) => Vec2::ZERO,
+    };
 
     for ContactForceEvent {
         collider1,
@@ -635,10 +759,12 @@ fn contacts(
                     let bite_size = b1.genome_bite_size;
                     b2.life_points = b2.life_points.saturating_sub(bite_size);
                     b1.life_points = b1.life_points.saturating_add(bite_size);
+                    events.bitten.write(Bitten { biter: id_of(collider1), victim: id_of(collider2), amount: bite_size, position: midpoint(collider1, collider2), force });
                 } else if !two_is_friendly && (v2.linvel.length().abs() > v1.linvel.length().abs()) {
                     let bite_size = b2.genome_bite_size;
                     b1.life_points = b1.life_points.saturating_sub(bite_size);
                     b2.life_points = b2.life_points.saturating_add(bite_size);
+                    events.bitten.write(Bitten { biter: id_of(collider2), victim: id_of(collider1), amount: bite_size, position: midpoint(collider1, collider2), force });
                 }
 
                 // Update visible colors by walking to BallRender child to find the material handle
@@ -743,10 +869,12 @@ fn contacts(
                         .build(),
                 ),
                 JointBorn { frame: frame_counter.frame },
+                JointWear::default(),
             ))
             .id();
         commands.entity(collider2).add_child(joint_entity);
         eprintln!("[diag] joint_create ok between {:?} and {:?} (child {:?})", collider1, collider2, joint_entity);
+        events.formed.write(JointFormed { a: id_of(collider1), b: id_of(collider2), position: midpoint(collider1, collider2), force });
         // Green label for successful stick
         if tuning.show_collision_labels {
             let display_force = force / PIXELS_PER_METER;
@@ -772,30 +900,29 @@ fn contacts(
     }
 }
 #[allow(clippy::too_many_arguments)]
-fn unstick(
+pub(crate) fn unstick(
     mut commands: Commands,
     rapier: bevy_rapier2d::prelude::ReadRapierContext,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 
     q_balls_with_children: Query<(Entity, &Children), With<Ball>>,
-    q_rapier_handles_with_bevy_impulse_joints: Query<
-        &RapierImpulseJointHandle,
-        With<BevyImpulseJoint>,
-    >,
-    q_joint_born: Query<&JointBorn>,
+    q_rapier_handles_with_bevy_impulse_joints: Query<(&RapierImpulseJointHandle, &BevyImpulseJoint)>,
+    mut q_joint_age: Query<(&JointBorn, &mut JointWear)>,
     q_global_transforms: Query<&GlobalTransform>,
     q_existing_markers: Query<(&Transform, &ForceMarker)>,
-    frame_counter: ResMut<FrameCounter>,
+    frame_counter: Res<FrameCounter>,
+    mut joint_stats: ResMut<JointLifetimeStats>,
 
     tuning: Res<crate::tuning::PhysicsTuning>,
-
+    q_ids: Query<&BallId>,
+    mut broken: EventWriter<JointBroken>,
 ) {
     for (_ball_entity, children) in q_balls_with_children.iter() {
 
         for child_entity in children.iter() {
-            let rapier_handle = match q_rapier_handles_with_bevy_impulse_joints.get(*child_entity) {
-                Ok(rapier_handle) => rapier_handle,
+            let (rapier_handle, bevy_impulse_joint) = match q_rapier_handles_with_bevy_impulse_joints.get(*child_entity) {
+                Ok(found) => found,
                 Err(_) => continue,
             };
             let bevy_impulse_joint_entity = child_entity;
@@ -806,8 +933,32 @@ fn unstick(
             };
             for impulse in rapier_joint.impulses.column_iter() {
                 let impulse_magnitude: f32 = Vec2::new(impulse.x, impulse.y).length();
-                if impulse_magnitude > tuning.break_force_threshold {
-                    eprintln!("[diag] joint_break impulse={impulse_magnitude:.6}");
+                // Aged and worn joints break below the nominal threshold
+                let (age_frames, strength) = match q_joint_age.get_mut(*bevy_impulse_joint_entity) {
+                    Ok((born, mut wear)) => {
+                        wear.accumulated_impulse += impulse_magnitude;
+                        let age_frames = frame_counter.frame.saturating_sub(born.frame);
+                        (Some(age_frames), joint_strength(age_frames, wear.accumulated_impulse, &tuning))
+                    }
+                    // Not a ball-to-ball joint (e.g. a sticky peg anchor): no aging, no lifetime stats
+                    Err(_) => (None, 1.0),
+                };
+                let effective_threshold = tuning.break_force_threshold * strength;
+                if impulse_magnitude > effective_threshold {
+                    eprintln!("[diag] joint_break impulse={impulse_magnitude:.6} threshold={effective_threshold:.6} age_frames={}", age_frames.unwrap_or(0));
+                    if let Some(age_frames) = age_frames {
+                        joint_stats.record_break(age_frames);
+                    }
+                    let id_of = |e: Entity| q_ids.get(e).ok().map(|id| id.0);
+                    let position = q_global_transforms.get(_ball_entity).map_or(Vec2::ZERO, |tf| tf.translation().truncate());
+                    broken.write(JointBroken {
+                        a: id_of(bevy_impulse_joint.parent),
+                        b: id_of(_ball_entity),
+                        position,
+                        impulse: impulse_magnitude,
+                        threshold: effective_threshold,
+                        age_frames,
+                    });
                     if tuning.show_break_labels && impulse_magnitude >= tuning.break_label_impulse_min {
                         // Spawn red marker at the parent ball's transform (joint entity has no Transform)
                         // Stack above nearby markers at the parent ball's position
@@ -834,6 +985,98 @@ fn unstick(
                     commands
                         .entity(*bevy_impulse_joint_entity)
                         .despawn();
+                    break;
+                }
+            }
+        }
+    }
+}
+
+// Wrap: lift balls that fell out of the bottom (together with every ball jointed to them) by one arena height,
+// or less if that would take the colony's top member above the ceiling; a colony too tall to fit leaves its
+// other members where they are. Recycle: remove balls that fell
+// through a drain, unless they are already dying of low life points, which reports their death instead.
+fn apply_boundary(
+    mut commands: Commands,
+    arena: Res<ArenaConfig>,
+    mut q_balls: Query<(Entity, &mut Transform), With<Ball>>,
+    q_impulse_joints: Query<(&BevyImpulseJoint, &bevy::prelude::ChildOf)>,
+    q_info: Query<(&Ball, Option<&BallId>)>,
+    mut died: EventWriter<BallDied>,
+) {
+    // Fully below the floor line
+    let exit_y = GROUND_POSITION - 2.0 * BALL_RADIUS;
+    match arena.boundary {
+        BoundaryMode::Closed => {}
+        BoundaryMode::Recycle => {
+            for (entity, transform) in q_balls.iter() {
+                if transform.translation.y < exit_y {
+                    let info = q_info.get(entity).ok();
+                    if info.is_some_and(|(ball, _)| ball.is_dying()) {
+                        continue;
+                    }
+                    let (id, age) = info.map_or((None, 0), |(ball, id)| (id.map(|id| id.0), ball.age));
+                    died.write(BallDied { id, cause: DeathCause::Drained, position: transform.translation.truncate(), age });
+                    commands.entity(entity).despawn();
+                }
+            }
+        }
+        BoundaryMode::Wrap => {
+            let fallen: Vec<Entity> = q_balls
+                .iter()
+                .filter(|(_, tf)| tf.translation.y < exit_y)
+                .map(|(e, _)| e)
+                .collect();
+            if fallen.is_empty() {
+                return;
+            }
+            // Ball-to-ball joint adjacency, so a colony moves as one and its joints stay intact. Peg anchors are
+            // left out: balls pinned to the same peg are not one colony.
+            let mut neighbors: std::collections::HashMap<Entity, Vec<Entity>> = std::collections::HashMap::new();
+            for (joint, child_of) in q_impulse_joints.iter() {
+                if !q_balls.contains(joint.parent) || !q_balls.contains(child_of.parent()) {
+                    continue;
+                }
+                neighbors.entry(joint.parent).or_default().push(child_of.parent());
+                neighbors.entry(child_of.parent()).or_default().push(joint.parent);
+            }
+            let mut lifted: std::collections::HashSet<Entity> = std::collections::HashSet::new();
+            let ceiling = GROUND_POSITION + WALL_HEIGHT - BALL_RADIUS;
+            for start in fallen {
+                if lifted.contains(&start) {
+                    continue;
+                }
+                let mut colony = Vec::new();
+                let mut stack = vec![start];
+                while let Some(entity) = stack.pop() {
+                    if !lifted.insert(entity) {
+                        continue;
+                    }
+                    colony.push(entity);
+                    if let Some(next) = neighbors.get(&entity) {
+                        stack.extend(next.iter().copied());
+                    }
+                }
+                // One offset for the whole colony, so its shape is kept: its top member stops at the ceiling,
+                // but its lowest member must end up back inside the arena
+                let heights = colony.iter().filter_map(|e| q_balls.get(*e).ok()).map(|(_, tf)| tf.translation.y);
+                let (lowest, top) = heights.fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), y| (lo.min(y), hi.max(y)));
+                let offset = WALL_HEIGHT.min(ceiling - top);
+                if lowest + offset >= exit_y + BALL_RADIUS {
+                    for entity in colony {
+                        if let Ok((_, mut transform)) = q_balls.get_mut(entity) {
+                            transform.translation.y += offset;
+                        }
+                    }
+                    continue;
+                }
+                // Taller than the arena can hold: bring the fallen members back on their own
+                for entity in colony {
+                    if let Ok((_, mut transform)) = q_balls.get_mut(entity) {
+                        if transform.translation.y < exit_y {
+                            transform.translation.y = (transform.translation.y + WALL_HEIGHT).min(ceiling);
+                        }
+                    }
                 }
             }
         }
@@ -847,11 +1090,268 @@ impl Plugin for BallPlugin {
         app.insert_resource(NewBallsTimer(Timer::from_seconds(2.0, TimerMode::Repeating)))
             .insert_resource(ReproduceBallsTimer(Timer::from_seconds(0.025, TimerMode::Repeating)))
             .insert_resource(BallAndJointLoopTimer(Timer::from_seconds(0.5, TimerMode::Repeating)))
+            .insert_resource(JointStatsLogTimer(Timer::from_seconds(10.0, TimerMode::Repeating)))
             .insert_resource(FrameCounter::default())
-            .add_systems(Update, (add_balls, reproduce_balls))
-            .add_systems(Update, contacts)
-            .add_systems(Update, unstick)
-            .add_systems(Update, update_force_markers)
-            .add_systems(Update, update_life_points);
+            .insert_resource(JointLifetimeStats::default())
+            // Simulation runs in the fixed-step schedule so SimTiming can fast-forward or slow it down;
+            // FrameCounter counts physics steps.
+            .add_systems(FixedFirst, (advance_frame_counter, publish_sim_clock).chain())
+            .add_systems(FixedUpdate, (add_balls, reproduce_balls).before(PhysicsSet::SyncBackend))
+            .add_systems(FixedUpdate, contacts.before(PhysicsSet::SyncBackend))
+            .add_systems(FixedUpdate, unstick.before(PhysicsSet::SyncBackend))
+            // One death per ball: a drained ball that is also out of life points dies of the latter
+            .add_systems(FixedUpdate, (update_life_points, apply_boundary).chain().before(PhysicsSet::SyncBackend))
+            .add_systems(Update, log_joint_lifetime_stats)
+            .add_systems(Update, update_force_markers);
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuning::PhysicsTuning;
+
+    #[test]
+    fn joints_weaken_with_age_and_load_down_to_the_floor() {
+        let tuning = PhysicsTuning {
+            joint_age_fatigue_rate: 0.001,
+            joint_impulse_fatigue_rate: 0.01,
+            joint_min_strength: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(joint_strength(0, 0.0, &tuning), 1.0);
+        assert!((joint_strength(100, 0.0, &tuning) - 0.9).abs() < 1e-6);
+        assert!((joint_strength(100, 10.0, &tuning) - 0.8).abs() < 1e-6);
+        assert_eq!(joint_strength(100_000, 0.0, &tuning), 0.25);
+
+        // Fatigue off (the default) leaves every joint at full strength
+        assert_eq!(joint_strength(1_000_000, 1.0e6, &PhysicsTuning::default()), 1.0);
+        // An out-of-range floor is clamped rather than trusted
+        let loose = PhysicsTuning { joint_age_fatigue_rate: 1.0, joint_min_strength: -1.0, ..Default::default() };
+        assert_eq!(joint_strength(10, 0.0, &loose), 0.0);
+    }
+
+    // Two balls pulled apart on one joint: the load a fresh joint holds breaks a worn one.
+    #[test]
+    fn worn_joint_breaks_under_load_a_fresh_one_holds() {
+        let mut app = crate::setup::physics_test_app();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .insert_resource(FrameCounter { frame: 1_000 })
+            .init_resource::<JointLifetimeStats>()
+            .insert_resource(PhysicsTuning { break_force_threshold: 1.0e30, joint_age_fatigue_rate: 0.0, joint_min_strength: 0.0, ..Default::default() })
+            .add_event::<JointBroken>()
+            .add_systems(Update, unstick);
+        let mut ball = |x: f32, pull: f32| {
+            app.world_mut()
+                .spawn((
+                    Ball::default(),
+                    RigidBody::Dynamic,
+                    Collider::ball(BALL_RADIUS),
+                    bevy_rapier2d::prelude::ExternalForce { force: Vec2::new(pull, 0.0), torque: 0.0 },
+                    Transform::from_xyz(x, 0.0, 0.0),
+                ))
+                .id()
+        };
+        let (a, b) = (ball(-BALL_RADIUS, -1.0e3), ball(BALL_RADIUS, 1.0e3));
+        let joint = app
+            .world_mut()
+            .spawn((
+                BevyImpulseJoint::new(a, RevoluteJointBuilder::new().local_anchor1(Vec2::new(BALL_RADIUS, 0.0)).local_anchor2(Vec2::new(-BALL_RADIUS, 0.0)).build()),
+                JointBorn { frame: 0 },
+                JointWear::default(),
+                bevy::prelude::ChildOf(b),
+            ))
+            .id();
+
+        for _ in 0..5 {
+            app.update();
+        }
+        assert!(app.world().get::<RapierImpulseJointHandle>(joint).is_some(), "a fresh joint holds");
+        assert_eq!(app.world().resource::<JointLifetimeStats>().breaks, 0);
+
+        // A thousand frames at this rate wear it down to the zero floor
+        app.world_mut().resource_mut::<PhysicsTuning>().joint_age_fatigue_rate = 0.01;
+        let mut broken = Vec::new();
+        for _ in 0..5 {
+            app.update();
+            broken.extend(app.world_mut().resource_mut::<bevy::prelude::Events<JointBroken>>().drain());
+        }
+        assert!(app.world().get_entity(joint).is_err(), "the worn joint gave way");
+        assert_eq!(app.world().resource::<JointLifetimeStats>().breaks, 1);
+        assert_eq!(broken.iter().map(|j| j.age_frames).collect::<Vec<_>>(), vec![Some(1_000)]);
     }
-}
\ No newline at end of file
+
+    // A sticky peg anchor has no age and no ids at either end, but its break is still reported.
+    #[test]
+    fn peg_anchor_break_is_reported_without_age() {
+        let mut app = crate::setup::physics_test_app();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .init_resource::<FrameCounter>()
+            .init_resource::<JointLifetimeStats>()
+            .insert_resource(PhysicsTuning { break_force_threshold: 1.0e-3, ..Default::default() })
+            .add_event::<JointBroken>()
+            .add_systems(Update, unstick);
+        let peg = app.world_mut().spawn((RigidBody::Fixed, Collider::ball(BALL_RADIUS), Transform::from_xyz(0.0, 0.0, 0.0))).id();
+        let ball = app
+            .world_mut()
+            .spawn((
+                Ball::default(),
+                RigidBody::Dynamic,
+                Collider::ball(BALL_RADIUS),
+                bevy_rapier2d::prelude::ExternalForce { force: Vec2::new(1.0e3, 0.0), torque: 0.0 },
+                Transform::from_xyz(2.0 * BALL_RADIUS, 0.0, 0.0),
+            ))
+            .id();
+        app.world_mut().spawn((
+            BevyImpulseJoint::new(peg, RevoluteJointBuilder::new().local_anchor1(Vec2::new(BALL_RADIUS, 0.0)).local_anchor2(Vec2::new(-BALL_RADIUS, 0.0)).build()),
+            bevy::prelude::ChildOf(ball),
+        ));
+
+        let mut broken = Vec::new();
+        for _ in 0..5 {
+            app.update();
+            broken.extend(app.world_mut().resource_mut::<bevy::prelude::Events<JointBroken>>().drain());
+        }
+        assert_eq!(broken.iter().map(|j| (j.a, j.b, j.age_frames)).collect::<Vec<_>>(), vec![(None, None, None)]);
+        assert_eq!(app.world().resource::<JointLifetimeStats>().breaks, 0, "anchors stay out of joint lifetimes");
+    }
+
+    fn boundary_app(boundary: BoundaryMode) -> App {
+        let mut app = App::new();
+        app.insert_resource(ArenaConfig { boundary, ..Default::default() })
+            .add_event::<BallDied>()
+            .add_systems(Update, apply_boundary);
+        app
+    }
+
+    fn spawn_ball(app: &mut App, y: f32) -> Entity {
+        app.world_mut().spawn((Ball::default(), Transform::from_xyz(0.0, y, 0.0))).id()
+    }
+
+    fn y_of(app: &App, entity: Entity) -> f32 {
+        app.world().get::<Transform>(entity).unwrap().translation.y
+    }
+
+    #[test]
+    fn wrap_lifts_a_fallen_ball_with_its_colony_under_the_ceiling() {
+        let mut app = boundary_app(BoundaryMode::Wrap);
+        let fallen_y = GROUND_POSITION - 3.0 * BALL_RADIUS;
+        let fallen = spawn_ball(&mut app, fallen_y);
+        let partner = spawn_ball(&mut app, fallen_y + BALL_RADIUS);
+        let loner = spawn_ball(&mut app, GROUND_POSITION + 10.0 * BALL_RADIUS);
+        app.world_mut().spawn((BevyImpulseJoint::new(fallen, RevoluteJointBuilder::new().build()), bevy::prelude::ChildOf(partner)));
+        app.update();
+
+        assert!((y_of(&app, fallen) - (fallen_y + WALL_HEIGHT)).abs() < 1e-3);
+        assert!((y_of(&app, partner) - y_of(&app, fallen) - BALL_RADIUS).abs() < 1e-3, "moved as one");
+        assert_eq!(y_of(&app, loner), GROUND_POSITION + 10.0 * BALL_RADIUS, "not jointed, not moved");
+    }
+
+    // A colony straddling the floor line is lifted only as far as its top member can go, keeping its shape.
+    #[test]
+    fn wrap_lifts_a_tall_colony_as_one_up_to_the_ceiling() {
+        let mut app = boundary_app(BoundaryMode::Wrap);
+        let fallen_y = GROUND_POSITION - 3.0 * BALL_RADIUS;
+        let top_y = GROUND_POSITION + 10.0 * BALL_RADIUS;
+        let fallen = spawn_ball(&mut app, fallen_y);
+        let partner = spawn_ball(&mut app, fallen_y + BALL_RADIUS);
+        let top = spawn_ball(&mut app, top_y);
+        for (parent, child) in [(fallen, partner), (partner, top)] {
+            app.world_mut().spawn((BevyImpulseJoint::new(parent, RevoluteJointBuilder::new().build()), bevy::prelude::ChildOf(child)));
+        }
+        app.update();
+
+        let ceiling = GROUND_POSITION + WALL_HEIGHT - BALL_RADIUS;
+        let lift = ceiling - top_y;
+        assert!((y_of(&app, top) - ceiling).abs() < 1e-3, "held under the ceiling");
+        assert!((y_of(&app, fallen) - (fallen_y + lift)).abs() < 1e-3);
+        assert!((y_of(&app, partner) - (fallen_y + BALL_RADIUS + lift)).abs() < 1e-3, "same offset for every member");
+    }
+
+    // A colony already reaching the ceiling cannot move up as one, so its fallen member comes back alone.
+    #[test]
+    fn wrap_lifts_the_fallen_ball_alone_when_its_colony_reaches_the_ceiling() {
+        let mut app = boundary_app(BoundaryMode::Wrap);
+        let ceiling = GROUND_POSITION + WALL_HEIGHT - BALL_RADIUS;
+        let fallen_y = GROUND_POSITION - 3.0 * BALL_RADIUS;
+        let fallen = spawn_ball(&mut app, fallen_y);
+        let partner = spawn_ball(&mut app, GROUND_POSITION + 10.0 * BALL_RADIUS);
+        let top = spawn_ball(&mut app, ceiling);
+        for (parent, child) in [(fallen, partner), (partner, top)] {
+            app.world_mut().spawn((BevyImpulseJoint::new(parent, RevoluteJointBuilder::new().build()), bevy::prelude::ChildOf(child)));
+        }
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert!((y_of(&app, fallen) - (fallen_y + WALL_HEIGHT)).abs() < 1e-3, "back in the arena, lifted once");
+        assert_eq!(y_of(&app, partner), GROUND_POSITION + 10.0 * BALL_RADIUS);
+        assert_eq!(y_of(&app, top), ceiling);
+    }
+
+    // Balls pinned to the same sticky peg are not a colony: only the fallen one is lifted.
+    #[test]
+    fn wrap_does_not_walk_through_peg_anchors() {
+        let mut app = boundary_app(BoundaryMode::Wrap);
+        let peg = app.world_mut().spawn(Transform::from_xyz(0.0, GROUND_POSITION, 0.0)).id();
+        let fallen_y = GROUND_POSITION - 3.0 * BALL_RADIUS;
+        let fallen = spawn_ball(&mut app, fallen_y);
+        let pinned = spawn_ball(&mut app, GROUND_POSITION + BALL_RADIUS);
+        for ball in [fallen, pinned] {
+            app.world_mut().spawn((BevyImpulseJoint::new(peg, RevoluteJointBuilder::new().build()), bevy::prelude::ChildOf(ball)));
+        }
+        app.update();
+
+        assert!((y_of(&app, fallen) - (fallen_y + WALL_HEIGHT)).abs() < 1e-3);
+        assert_eq!(y_of(&app, pinned), GROUND_POSITION + BALL_RADIUS, "left on its peg");
+        assert_eq!(y_of(&app, peg), GROUND_POSITION);
+    }
+
+    #[test]
+    fn recycle_drains_fallen_balls_and_closed_leaves_them() {
+        let below = GROUND_POSITION - 3.0 * BALL_RADIUS;
+        let mut app = boundary_app(BoundaryMode::Recycle);
+        let fallen = spawn_ball(&mut app, below);
+        let standing = spawn_ball(&mut app, GROUND_POSITION + BALL_RADIUS);
+        app.update();
+        assert!(app.world().get_entity(fallen).is_err());
+        assert!(app.world().get_entity(standing).is_ok());
+        let died: Vec<BallDied> = app.world_mut().resource_mut::<bevy::prelude::Events<BallDied>>().drain().collect();
+        assert_eq!(died.len(), 1);
+        assert_eq!(died[0].cause, DeathCause::Drained);
+
+        let mut app = boundary_app(BoundaryMode::Closed);
+        let fallen = spawn_ball(&mut app, below);
+        app.update();
+        assert_eq!(y_of(&app, fallen), below);
+    }
+
+    // A drained ball whose life runs out on the same step is reported once, as the life-point death.
+    #[test]
+    fn ball_drained_as_its_life_runs_out_dies_once() {
+        let mut app = App::new();
+        app.insert_resource(ArenaConfig { boundary: BoundaryMode::Recycle, ..Default::default() })
+            .insert_resource(BallAndJointLoopTimer(Timer::from_seconds(0.0, TimerMode::Once)))
+            .insert_resource(RngResource::seeded(1))
+            .insert_resource(PhysicsTuning::default())
+            .init_resource::<Time>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .add_event::<BallDied>()
+            .add_systems(Update, (update_life_points, apply_boundary).chain());
+        let ball = app
+            .world_mut()
+            .spawn((
+                Ball { life_points: 5, ..Default::default() },
+                BallId(3),
+                MeshMaterial2d::<ColorMaterial>(Handle::default()),
+                Transform::from_xyz(0.0, GROUND_POSITION - 3.0 * BALL_RADIUS, 0.0),
+            ))
+            .id();
+        app.update();
+
+        assert!(app.world().get_entity(ball).is_err());
+        let died: Vec<BallDied> = app.world_mut().resource_mut::<bevy::prelude::Events<BallDied>>().drain().collect();
+        assert_eq!(died.iter().map(|d| (d.id, d.cause)).collect::<Vec<_>>(), vec![(Some(3), DeathCause::Starved)]);
+    }
+}
@@ -0,0 +1,267 @@
+
+use bevy::prelude::{
+    App, Assets, ChildOf, ColorMaterial, Commands, Component, Entity, EventWriter, IntoScheduleConfigs, Last, NonSend, Plugin,
+    Query, Res, ResMut, Transform, Update, Vec2, With, Without,
+};
+use bevy_rapier2d::prelude::{ExternalImpulse, ImpulseJoint, Velocity};
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ball::{ball_bundle, random_ball, Ball, MAX_LIFE_POINTS},
+    balls::{BallCommand, BallEdit, BallIds, BallInfo, BallRx, BallsMirror, BallsSnapshot, GenomeInfo, GenomeUpdate},
+    events::{BallBorn, BallDied, DeathCause},
+    query::JointInfo,
+    setup::{ContactForceThreshold, MeshAssets2d, RngResource},
+    sim::SimClock,
+    tuning::PhysicsTuning,
+};
+
+/// Stable ball id for the HTTP API; unlike `Entity`, never reused.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BallId(pub u64);
+
+// Every spawn path takes an id from BallIds as it spawns; this catches any ball that slipped through.
+fn assign_ball_ids(mut commands: Commands, ids: Res<BallIds>, q_new: Query<Entity, (With<Ball>, Without<BallId>)>) {
+    for entity in q_new.iter() {
+        commands.entity(entity).insert(BallId(ids.next()));
+    }
+}
+
+fn genome_info(ball: &Ball) -> GenomeInfo {
+    GenomeInfo {
+        max_age: ball.genome_max_age,
+        relative_reproduction_rate: ball.genome_relative_reproduction_rate,
+        bite_size: ball.genome_bite_size,
+        life_points_safe_to_reproduce: ball.genome_life_points_safe_to_reproduce,
+        energy_share_with_children: ball.genome_energy_share_with_children,
+        friendly_scent: ball.genome_friendly_scent.into(),
+        friendly_distance: ball.genome_friendly_distance,
+    }
+}
+
+fn apply_genome(ball: &mut Ball, update: &GenomeUpdate) {
+    let mut g = genome_info(ball);
+    update.apply_to(&mut g);
+    ball.genome_max_age = g.max_age;
+    ball.genome_relative_reproduction_rate = g.relative_reproduction_rate;
+    ball.genome_bite_size = g.bite_size;
+    ball.genome_life_points_safe_to_reproduce = g.life_points_safe_to_reproduce;
+    ball.genome_energy_share_with_children = g.energy_share_with_children;
+    ball.genome_friendly_scent = g.friendly_scent.into();
+    ball.genome_friendly_distance = g.friendly_distance;
+}
+
+// Runs in Update rather than the fixed loop so edits land while the simulation is paused.
+#[allow(clippy::too_many_arguments)]
+fn apply_ball_commands(
+    rx: Option<NonSend<BallRx>>,
+    mut commands: Commands,
+    mut q_balls: Query<(Entity, &BallId, &mut Ball, &Transform)>,
+    q_impulses: Query<&ExternalImpulse>,
+    mut rng_resource: ResMut<RngResource>,
+    mesh_assets: Res<MeshAssets2d>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    tuning: Res<PhysicsTuning>,
+    contact_force_threshold: Res<ContactForceThreshold>,
+    mut born: EventWriter<BallBorn>,
+    mut died: EventWriter<BallDied>,
+) {
+    let Some(rx) = rx else { return; };
+    // Despawns and inserts are deferred, so repeats within this frame are settled here
+    let mut killed: HashSet<Entity> = HashSet::new();
+    let mut impulses: HashMap<Entity, Vec2> = HashMap::new();
+    while let Ok(cmd) = rx.0.try_recv() {
+        let (id, edit) = match cmd {
+            BallCommand::Spawn { id, spec } => {
+                let mut ball = random_ball(&mut rng_resource.rng, &tuning);
+                apply_genome(&mut ball, &spec.genome);
+                if let Some(life) = spec.life_points {
+                    ball.life_points = life.min(MAX_LIFE_POINTS);
+                }
+                born.write(BallBorn { id, parent: None, position: Vec2::new(spec.x, spec.y), life_points: ball.life_points });
+                commands.spawn((
+                    ball_bundle(
+                        ball,
+                        Vec2::new(spec.x, spec.y),
+                        Vec2::new(spec.vx, spec.vy),
+                        mesh_assets.ball_circle.clone(),
+                        &mut materials,
+                        contact_force_threshold.0,
+                    ),
+                    BallId(id),
+                ));
+                continue;
+            }
+            BallCommand::Edit { id, edit } => (id, edit),
+        };
+        // The ball may have died since the handler checked the snapshot
+        let found = q_balls.iter_mut().find(|(entity, ball_id, _, _)| ball_id.0 == id && !killed.contains(entity));
+        let Some((entity, _, mut ball, transform)) = found else {
+            eprintln!("[diag] api ball {id} is gone; dropping {edit:?}");
+            continue;
+        };
+        match edit {
+            BallEdit::Kill => {
+                died.write(BallDied { id: Some(id), cause: DeathCause::Killed, position: transform.translation.truncate(), age: ball.age });
+                commands.entity(entity).despawn();
+                killed.insert(entity);
+            }
+            BallEdit::Impulse(impulse) => {
+                let impulse: Vec2 = impulse.into();
+                *impulses.entry(entity).or_default() += impulse;
+            }
+            BallEdit::Genome(update) => apply_genome(&mut ball, &update),
+            BallEdit::AdjustLife(delta) => {
+                let life = (ball.life_points as i64).saturating_add(delta);
+                ball.life_points = life.clamp(0, MAX_LIFE_POINTS as i64) as u32;
+            }
+        }
+    }
+    // Every impulse counts, on top of any the physics step has not applied yet
+    for (entity, impulse) in impulses {
+        if killed.contains(&entity) {
+            continue;
+        }
+        let pending = q_impulses.get(entity).copied().unwrap_or_default();
+        commands.entity(entity).insert(ExternalImpulse { impulse: pending.impulse + impulse, ..pending });
+    }
+}
+
+pub(crate) fn ball_info(id: BallId, ball: &Ball, transform: &Transform, velocity: Option<&Velocity>) -> BallInfo {
+    let linvel = velocity.map(|v| v.linvel).unwrap_or_default();
+    BallInfo {
+        id: id.0,
+        x: transform.translation.x,
+        y: transform.translation.y,
+        vx: linvel.x,
+        vy: linvel.y,
+        age: ball.age,
+        life_points: ball.life_points,
+        genome: genome_info(ball),
+        joints: Vec::new(),
+    }
+}
+
+/// Fill in `joints` on `balls` (sorted by id) for every ball-to-ball joint with an end among them,
+/// and return those joints, sorted. Joints hang off one ball as a child and point at the other;
+/// joints to pegs and walls have no partner and are skipped.
+pub(crate) fn link_joint_partners(balls: &mut [BallInfo], q_joints: &Query<(&ImpulseJoint, &ChildOf)>, q_ids: &Query<&BallId>) -> Vec<JointInfo> {
+    let mut joints = Vec::new();
+    for (joint, child_of) in q_joints.iter() {
+        let (Ok(a), Ok(b)) = (q_ids.get(child_of.parent()), q_ids.get(joint.parent)) else { continue; };
+        let (ia, ib) = (balls.binary_search_by_key(&a.0, |x| x.id).ok(), balls.binary_search_by_key(&b.0, |x| x.id).ok());
+        if ia.is_none() && ib.is_none() {
+            continue;
+        }
+        if let Some(i) = ia {
+            balls[i].joints.push(b.0);
+        }
+        if let Some(i) = ib {
+            balls[i].joints.push(a.0);
+        }
+        joints.push(JointInfo { a: a.0.min(b.0), b: a.0.max(b.0) });
+    }
+    joints.sort_by_key(|j| (j.a, j.b));
+    joints
+}
+
+// Snapshot every ball once per frame so handlers never touch the world; also covers API edits made while paused.
+fn publish_balls(
+    mirror: Option<Res<BallsMirror>>,
+    clock: Option<Res<SimClock>>,
+    q_balls: Query<(&BallId, &Ball, &Transform, Option<&Velocity>)>,
+    q_ids: Query<&BallId>,
+    q_joints: Query<(&ImpulseJoint, &ChildOf)>,
+) {
+    let Some(mirror) = mirror else { return; };
+    let mut balls: Vec<BallInfo> = q_balls.iter().map(|(id, ball, transform, velocity)| ball_info(*id, ball, transform, velocity)).collect();
+    balls.sort_by_key(|b| b.id);
+    link_joint_partners(&mut balls, &q_joints, &q_ids);
+    let (frame, sim_seconds) = clock.map_or((0, 0.0), |c| (c.frame(), c.seconds()));
+    *mirror.0.lock().unwrap() = BallsSnapshot { frame, sim_seconds, balls };
+}
+
+pub struct BallSystemsPlugin;
+
+impl Plugin for BallSystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallIds>()
+            .add_systems(Update, apply_ball_commands)
+            .add_systems(Last, (assign_ball_ids, publish_balls).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balls::SpawnBall;
+    use bevy::prelude::{Events, Handle};
+    use std::sync::mpsc;
+
+    fn commands_app() -> (App, mpsc::Sender<BallCommand>) {
+        let (tx, rx) = mpsc::channel();
+        let mut app = App::new();
+        app.insert_non_send_resource(BallRx(rx))
+            .insert_resource(RngResource::seeded(1))
+            .insert_resource(MeshAssets2d { ball_circle: Handle::default() })
+            .init_resource::<Assets<ColorMaterial>>()
+            .insert_resource(PhysicsTuning::default())
+            .insert_resource(ContactForceThreshold::default())
+            .add_event::<BallBorn>()
+            .add_event::<BallDied>()
+            .add_systems(Update, apply_ball_commands);
+        (app, tx)
+    }
+
+    fn ball(app: &mut App, id: u64) -> Option<(Ball, Vec2)> {
+        let mut q = app.world_mut().query::<(&BallId, &Ball, &Transform)>();
+        q.iter(app.world()).find(|(b, _, _)| b.0 == id).map(|(_, ball, t)| (*ball, t.translation.truncate()))
+    }
+
+    #[test]
+    fn commands_spawn_edit_and_kill_balls() {
+        let (mut app, tx) = commands_app();
+        let genome = GenomeUpdate { bite_size: Some(9), ..Default::default() };
+        tx.send(BallCommand::Spawn { id: 7, spec: SpawnBall { x: 10.0, y: 20.0, life_points: Some(123), genome, ..Default::default() } }).unwrap();
+        app.update();
+        let (spawned, position) = ball(&mut app, 7).expect("spawned");
+        assert_eq!((spawned.life_points, spawned.genome_bite_size, position), (123, 9, Vec2::new(10.0, 20.0)));
+        let born: Vec<BallBorn> = app.world_mut().resource_mut::<Events<BallBorn>>().drain().collect();
+        assert_eq!(born.iter().map(|b| (b.id, b.parent, b.life_points)).collect::<Vec<_>>(), vec![(7, None, 123)]);
+
+        tx.send(BallCommand::Edit { id: 7, edit: BallEdit::AdjustLife(-1000) }).unwrap();
+        tx.send(BallCommand::Edit { id: 7, edit: BallEdit::Genome(GenomeUpdate { max_age: Some(5), ..Default::default() }) }).unwrap();
+        // A ball that died before its edit arrived is skipped
+        tx.send(BallCommand::Edit { id: 8, edit: BallEdit::Kill }).unwrap();
+        app.update();
+        let (edited, _) = ball(&mut app, 7).unwrap();
+        assert_eq!((edited.life_points, edited.genome_max_age, edited.genome_bite_size), (0, 5, 9));
+
+        tx.send(BallCommand::Edit { id: 7, edit: BallEdit::Kill }).unwrap();
+        app.update();
+        assert!(ball(&mut app, 7).is_none());
+        let died: Vec<BallDied> = app.world_mut().resource_mut::<Events<BallDied>>().drain().collect();
+        assert_eq!(died.iter().map(|d| (d.id, d.cause)).collect::<Vec<_>>(), vec![(Some(7), DeathCause::Killed)]);
+    }
+
+    #[test]
+    fn repeated_commands_in_one_frame_add_up_or_drop() {
+        let (mut app, tx) = commands_app();
+        tx.send(BallCommand::Spawn { id: 3, spec: SpawnBall::default() }).unwrap();
+        tx.send(BallCommand::Spawn { id: 4, spec: SpawnBall::default() }).unwrap();
+        app.update();
+
+        for impulse in [[1.0, 2.0], [3.0, -1.0]] {
+            tx.send(BallCommand::Edit { id: 3, edit: BallEdit::Impulse(impulse) }).unwrap();
+        }
+        tx.send(BallCommand::Edit { id: 4, edit: BallEdit::Kill }).unwrap();
+        tx.send(BallCommand::Edit { id: 4, edit: BallEdit::Kill }).unwrap();
+        app.update();
+
+        let mut q = app.world_mut().query::<(&BallId, &ExternalImpulse)>();
+        let pushed: Vec<(u64, Vec2)> = q.iter(app.world()).map(|(id, i)| (id.0, i.impulse)).collect();
+        assert_eq!(pushed, vec![(3, Vec2::new(4.0, 1.0))], "both impulses count");
+        let died: Vec<BallDied> = app.world_mut().resource_mut::<Events<BallDied>>().drain().collect();
+        assert_eq!(died.iter().map(|d| d.id).collect::<Vec<_>>(), vec![Some(4)], "killed once");
+    }
+}
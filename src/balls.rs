@@ -0,0 +1,312 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, patch, post},
+    Json, Router,
+};
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::inside_walls;
+
+/// Largest page GET /balls returns.
+pub const MAX_BALLS_PAGE: usize = 1000;
+
+/// A ball's heritable traits, as drawn from the tuning's genome ranges at birth.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GenomeInfo {
+    pub max_age: u32,
+    pub relative_reproduction_rate: f32,
+    pub bite_size: u32,
+    pub life_points_safe_to_reproduce: u32,
+    pub energy_share_with_children: f32,
+    pub friendly_scent: [f32; 2],
+    pub friendly_distance: f32,
+}
+
+/// One ball as seen by API clients. Positions are in pixels, velocities in pixels/s.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BallInfo {
+    /// Assigned once at spawn and never reused
+    pub id: u64,
+    pub x: f32,
+    pub y: f32,
+    pub vx: f32,
+    pub vy: f32,
+    /// In life ticks (0.5 simulated seconds)
+    pub age: u32,
+    pub life_points: u32,
+    pub genome: GenomeInfo,
+    /// Ids of the balls this one is jointed to, once per joint
+    pub joints: Vec<u64>,
+}
+
+/// Every ball at one physics step, sorted by id.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BallsSnapshot {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    pub balls: Vec<BallInfo>,
+}
+
+/// Snapshot published by the Bevy world each physics step for GET /balls.
+#[derive(Resource, Clone)]
+pub struct BallsMirror(pub Arc<Mutex<BallsSnapshot>>);
+
+/// Ball id allocator shared by the world (for balls it spawns) and the server (for balls it asks for),
+/// so POST /balls can answer with the id before the ball exists.
+#[derive(Resource, Clone, Default)]
+pub struct BallIds(Arc<AtomicU64>);
+
+impl BallIds {
+    pub fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::Relaxed) + 1
+    }
+}
+
+/// Genome fields to overwrite; omitted fields keep their value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenomeUpdate {
+    pub max_age: Option<u32>,
+    pub relative_reproduction_rate: Option<f32>,
+    pub bite_size: Option<u32>,
+    pub life_points_safe_to_reproduce: Option<u32>,
+    pub energy_share_with_children: Option<f32>,
+    pub friendly_scent: Option<[f32; 2]>,
+    pub friendly_distance: Option<f32>,
+}
+
+impl GenomeUpdate {
+    pub fn apply_to(&self, g: &mut GenomeInfo) {
+        if let Some(v) = self.max_age { g.max_age = v; }
+        if let Some(v) = self.relative_reproduction_rate { g.relative_reproduction_rate = v; }
+        if let Some(v) = self.bite_size { g.bite_size = v; }
+        if let Some(v) = self.life_points_safe_to_reproduce { g.life_points_safe_to_reproduce = v; }
+        if let Some(v) = self.energy_share_with_children { g.energy_share_with_children = v; }
+        if let Some(v) = self.friendly_scent { g.friendly_scent = v; }
+        if let Some(v) = self.friendly_distance { g.friendly_distance = v; }
+    }
+
+    // Rates are fractions; everything else just has to be a usable number
+    fn check(&self) -> Result<(), String> {
+        let fraction = |name: &str, v: Option<f32>| match v {
+            Some(v) if !(0.0..=1.0).contains(&v) => Err(format!("genome.{name} must be in [0, 1], got {v}")),
+            _ => Ok(()),
+        };
+        fraction("relative_reproduction_rate", self.relative_reproduction_rate)?;
+        fraction("energy_share_with_children", self.energy_share_with_children)?;
+        if self.friendly_scent.is_some_and(|s| s.iter().any(|v| !v.is_finite())) {
+            return Err("genome.friendly_scent must be finite".to_string());
+        }
+        match self.friendly_distance {
+            Some(v) if !(v.is_finite() && v >= 0.0) => Err(format!("genome.friendly_distance must be >= 0, got {v}")),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Body of POST /balls. Genome fields that are left out are drawn from the tuning's genome ranges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpawnBall {
+    pub x: f32,
+    pub y: f32,
+    /// Pixels/s
+    #[serde(default)]
+    pub vx: f32,
+    #[serde(default)]
+    pub vy: f32,
+    /// Defaults to full life
+    pub life_points: Option<u32>,
+    #[serde(default)]
+    pub genome: GenomeUpdate,
+}
+
+/// Commands from the HTTP server to the Bevy world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BallCommand {
+    Spawn { id: u64, spec: SpawnBall },
+    /// A change to a live ball; dropped if it has died by the time the world gets to it
+    Edit { id: u64, edit: BallEdit },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BallEdit {
+    Kill,
+    /// Rapier impulse, applied on the next physics step
+    Impulse([f32; 2]),
+    Genome(GenomeUpdate),
+    /// Added to life points, saturating at 0 and the maximum
+    AdjustLife(i64),
+}
+
+// Not a Resource; see TuningRx.
+pub struct BallRx(pub mpsc::Receiver<BallCommand>);
+
+/// Server-side handle to the ball snapshot and command channel.
+#[derive(Clone)]
+pub struct BallsApi {
+    tx: mpsc::Sender<BallCommand>,
+    mirror: Arc<Mutex<BallsSnapshot>>,
+    ids: BallIds,
+}
+
+impl BallsApi {
+    /// Create the server handle plus the receiver, mirror and id allocator the Bevy world needs.
+    pub fn channel() -> (Self, BallRx, BallsMirror, BallIds) {
+        let (tx, rx) = mpsc::channel();
+        let mirror = Arc::new(Mutex::new(BallsSnapshot::default()));
+        let ids = BallIds::default();
+        (Self { tx, mirror: mirror.clone(), ids: ids.clone() }, BallRx(rx), BallsMirror(mirror), ids)
+    }
+
+    fn send(&self, cmd: BallCommand) -> Result<(), (StatusCode, String)> {
+        self.tx.send(cmd).map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "simulation is not running".to_string()))
+    }
+
+    // Commands for a ball that is not in the latest snapshot are refused rather than silently dropped
+    fn known(&self, id: u64) -> Result<BallInfo, (StatusCode, String)> {
+        let snapshot = self.mirror.lock().unwrap();
+        match snapshot.balls.binary_search_by_key(&id, |b| b.id) {
+            Ok(idx) => Ok(snapshot.balls[idx].clone()),
+            Err(_) => Err((StatusCode::NOT_FOUND, format!("no ball {id}"))),
+        }
+    }
+}
+
+/// Query string of GET /balls. Every filter is inclusive and optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BallsQuery {
+    #[serde(default)]
+    pub offset: usize,
+    /// Default 100, at most MAX_BALLS_PAGE
+    pub limit: Option<usize>,
+    pub min_age: Option<u32>,
+    pub max_age: Option<u32>,
+    pub min_life: Option<u32>,
+    pub max_life: Option<u32>,
+    /// Axis-aligned region in pixels
+    pub x_min: Option<f32>,
+    pub x_max: Option<f32>,
+    pub y_min: Option<f32>,
+    pub y_max: Option<f32>,
+}
+
+impl BallsQuery {
+    pub fn matches(&self, b: &BallInfo) -> bool {
+        fn within<T: PartialOrd>(v: T, min: Option<T>, max: Option<T>) -> bool {
+            min.is_none_or(|m| v >= m) && max.is_none_or(|m| v <= m)
+        }
+        within(b.age, self.min_age, self.max_age)
+            && within(b.life_points, self.min_life, self.max_life)
+            && within(b.x, self.x_min, self.x_max)
+            && within(b.y, self.y_min, self.y_max)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BallsPage {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    /// Balls matching the filters, before pagination
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub balls: Vec<BallInfo>,
+}
+
+async fn list_balls(State(api): State<BallsApi>, Query(q): Query<BallsQuery>) -> Json<BallsPage> {
+    let limit = q.limit.unwrap_or(100).min(MAX_BALLS_PAGE);
+    let snapshot = api.mirror.lock().unwrap();
+    let matching: Vec<&BallInfo> = snapshot.balls.iter().filter(|b| q.matches(b)).collect();
+    Json(BallsPage {
+        frame: snapshot.frame,
+        sim_seconds: snapshot.sim_seconds,
+        total: matching.len(),
+        offset: q.offset,
+        limit,
+        balls: matching.into_iter().skip(q.offset).take(limit).cloned().collect(),
+    })
+}
+
+async fn get_ball(State(api): State<BallsApi>, Path(id): Path<u64>) -> Result<Json<BallInfo>, StatusCode> {
+    let snapshot = api.mirror.lock().unwrap();
+    let idx = snapshot.balls.binary_search_by_key(&id, |b| b.id).map_err(|_| StatusCode::NOT_FOUND)?;
+    Ok(Json(snapshot.balls[idx].clone()))
+}
+
+type BallResult<T> = Result<T, (StatusCode, String)>;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SpawnedBall {
+    pub id: u64,
+}
+
+// Answers 202: the ball appears in GET /balls once the world has spawned it.
+async fn spawn_ball(State(api): State<BallsApi>, Json(spec): Json<SpawnBall>) -> BallResult<(StatusCode, Json<SpawnedBall>)> {
+    if ![spec.x, spec.y, spec.vx, spec.vy].iter().all(|v| v.is_finite()) {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "position and velocity must be finite".to_string()));
+    }
+    if !inside_walls(spec.x, spec.y) {
+        return Err((StatusCode::BAD_REQUEST, format!("({}, {}) is outside the arena", spec.x, spec.y)));
+    }
+    spec.genome.check().map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    let id = api.ids.next();
+    api.send(BallCommand::Spawn { id, spec })?;
+    eprintln!("[diag] api spawn ball id={id} at ({:.1},{:.1})", spec.x, spec.y);
+    Ok((StatusCode::ACCEPTED, Json(SpawnedBall { id })))
+}
+
+async fn kill_ball(State(api): State<BallsApi>, Path(id): Path<u64>) -> BallResult<StatusCode> {
+    api.known(id)?;
+    api.send(BallCommand::Edit { id, edit: BallEdit::Kill })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ImpulseRequest {
+    pub x: f32,
+    pub y: f32,
+}
+
+async fn impulse_ball(State(api): State<BallsApi>, Path(id): Path<u64>, Json(req): Json<ImpulseRequest>) -> BallResult<StatusCode> {
+    if !(req.x.is_finite() && req.y.is_finite()) {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, "impulse must be finite".to_string()));
+    }
+    api.known(id)?;
+    api.send(BallCommand::Edit { id, edit: BallEdit::Impulse([req.x, req.y]) })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+// Returns the ball as it will look once the edit lands.
+async fn edit_genome(State(api): State<BallsApi>, Path(id): Path<u64>, Json(update): Json<GenomeUpdate>) -> BallResult<Json<BallInfo>> {
+    update.check().map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    let mut ball = api.known(id)?;
+    api.send(BallCommand::Edit { id, edit: BallEdit::Genome(update) })?;
+    update.apply_to(&mut ball.genome);
+    Ok(Json(ball))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LifeRequest {
+    /// Positive gives, negative takes
+    pub delta: i64,
+}
+
+async fn adjust_life(State(api): State<BallsApi>, Path(id): Path<u64>, Json(req): Json<LifeRequest>) -> BallResult<StatusCode> {
+    api.known(id)?;
+    api.send(BallCommand::Edit { id, edit: BallEdit::AdjustLife(req.delta) })?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+pub fn balls_routes(api: BallsApi) -> Router {
+    Router::new()
+        .route("/balls", get(list_balls).post(spawn_ball))
+        .route("/balls/{id}", get(get_ball).delete(kill_ball))
+        .route("/balls/{id}/impulse", post(impulse_ball))
+        .route("/balls/{id}/genome", patch(edit_genome))
+        .route("/balls/{id}/life", post(adjust_life))
+        .with_state(api)
+}
@@ -106,6 +106,7 @@ fn forward_frames_to_ffmpeg(
     receiver: Option<Res<MainWorldReceiver>>,
     sender: Option<Res<FrameSender>>,
     cfg: Option<Res<crate::setup::VideoExportRequest>>,
+    metrics: Option<Res<crate::metrics::Metrics>>,
 ) {
     let (Some(rx), Some(sender), Some(cfg)) = (receiver, sender, cfg) else { return; };
     let row_bytes = (cfg.width as usize) * 4;
@@ -113,18 +114,27 @@ fn forward_frames_to_ffmpeg(
     let tx = &sender.tx;
     // Drain all available frames and forward the last one
     let mut last: Option<Vec<u8>> = None;
-    while let Ok(bytes) = rx.try_recv() { last = Some(bytes); }
-    if let Some(img) = last {
-        if aligned == row_bytes {
-            let _ = tx.send(img);
-        } else {
-            // shrink rows
-            let mut out = Vec::with_capacity(row_bytes * (cfg.height as usize));
-            for row in img.chunks(aligned).take(cfg.height as usize) {
-                out.extend_from_slice(&row[..row_bytes.min(row.len())]);
-            }
-            let _ = tx.send(out);
+    let mut drained = 0;
+    while let Ok(bytes) = rx.try_recv() { last = Some(bytes); drained += 1; }
+    if let Some(m) = &metrics {
+        m.capture.captured(drained);
+        m.capture.dropped(drained.saturating_sub(1));
+    }
+    let Some(img) = last else { return; };
+    let frame = if aligned == row_bytes {
+        img
+    } else {
+        // shrink rows
+        let mut out = Vec::with_capacity(row_bytes * (cfg.height as usize));
+        for row in img.chunks(aligned).take(cfg.height as usize) {
+            out.extend_from_slice(&row[..row_bytes.min(row.len())]);
         }
+        out
+    };
+    // A closed channel means the ffmpeg writer is gone; the frame goes nowhere
+    let sent = tx.send(frame).is_ok();
+    if let Some(m) = &metrics {
+        if sent { m.capture.queued(); } else { m.capture.dropped(1); }
     }
 }
 
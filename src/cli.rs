@@ -0,0 +1,352 @@
+use std::path::PathBuf;
+
+use crate::sim::DEFAULT_SEED;
+use crate::termination::StopConditions;
+
+pub const HELP: &str = "\
+live-whirl: a ball-and-peg evolution sandbox, recorded to MP4 and steerable over HTTP
+
+USAGE:
+    live-whirl [COMMAND] [OPTIONS]
+
+COMMANDS:
+    run      Live run: record, stream the UDP preview and serve HTTP, paced to --fps (default)
+    render   Offline recording: no preview, no HTTP unless --http-addr is given, as fast as possible
+    sweep    Render once per seed in --seeds, one after another and without HTTP, into
+             <output-dir>/sweep-<time>/seed-<n>
+
+OPTIONS:                              (environment fallback in brackets)
+    --seed <n>             World seed                                        [SEED, default 42]
+    --resolution <WxH>     Video size in pixels                              [VIDEO_RESOLUTION, default 1080x1920]
+    --fps <n>              Video frames per second                           [VIDEO_FPS, default 60]
+    --output-dir <dir>     Videos, logs and presets go under here            [OUTPUT_DIR, default ./output]
+    --http-addr <addr>     HTTP server address; a bare port means localhost  [HTTP_ADDR, default 127.0.0.1:7878]
+    --no-video             Simulate without capturing or encoding video      [VIDEO_EXPORT=0]
+    --duration <seconds>   Stop after this many seconds of video time        [RUN_DURATION]
+    --seeds <list>         Seeds for sweep, as 1,2,3 or 1..8 (inclusive)     [SWEEP_SEEDS]
+    --windowed             Open a window instead of recording                [WINDOWED]
+
+STOP CONDITIONS: the first one met ends the run like Ctrl+C and writes <recording>.exit.json
+    --max-frames <n>       Physics steps                                     [MAX_FRAMES]
+    --max-sim-seconds <s>  Simulated seconds                                 [MAX_SIM_SECONDS]
+    --stop-on-extinction   Every ball has died                               [STOP_ON_EXTINCTION]
+    --max-population <n>   More than n balls alive                           [MAX_POPULATION]
+    --dominance <share>    One scent holds this share (0.5-1] of 20+ balls   [STOP_DOMINANCE]
+
+TUNING AND HTTP:
+    --config <file>        Startup tuning file (.toml or .json)              [TUNING_CONFIG]
+    --set <field=value>    Override one tuning field (repeatable)            [TUNING_<FIELD>]
+    --http-token <t>       Bearer token required for mutating requests       [HTTP_TOKEN]
+    --cors-origin <url>    Allowed browser origin (repeatable)               [HTTP_CORS_ORIGINS]
+    -h, --help             Print this help
+
+Other environment: UDP_HOST, UDP_PORT, PREVIEW_URL (preview sinks), PHYSICS_HZ, PHYSICS_SUBSTEPS,
+SIM_SPEED, TUNING_PRESET, ARENA_CONFIG, BOUNDARY_MODE, DRAIN_COUNT, DRAIN_WIDTH, EVENT_LOG.
+";
+
+/// Environment a sweep's children must not inherit: no HTTP server, and each seed's event log
+/// goes to its own directory rather than one shared file.
+pub const SWEEP_CHILD_UNSET_ENV: &[&str] = &["HTTP_ADDR", "EVENT_LOG"];
+
+const VALUE_FLAGS: &[&str] = &[
+    "--seed",
+    "--resolution",
+    "--fps",
+    "--output-dir",
+    "--http-addr",
+    "--duration",
+    "--seeds",
+    "--config",
+    "--set",
+    "--http-token",
+    "--cors-origin",
+    "--max-frames",
+    "--max-sim-seconds",
+    "--max-population",
+    "--dominance",
+];
+const SWITCHES: &[&str] = &["--no-video", "--windowed", "--stop-on-extinction"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Run,
+    Render,
+    Sweep,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    /// `--help` was asked for; print HELP and exit successfully
+    Help,
+    Invalid(String),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Help => write!(f, "help requested"),
+            Self::Invalid(reason) => write!(f, "{reason} (see --help)"),
+        }
+    }
+}
+
+/// The command line split once into option values, switches and bare words. Cli reads its own
+/// options from it; TuningConfig and HttpConfig take their flags from `Cli::flags`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Flags {
+    values: Vec<(&'static str, String)>,
+    switches: Vec<&'static str>,
+    words: Vec<String>,
+}
+
+impl Flags {
+    /// `args` includes the program name, as from `std::env::args()`. Values may follow as the next
+    /// argument or after `=`.
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        let mut flags = Self::default();
+        let mut it = args.iter().skip(1);
+        while let Some(arg) = it.next() {
+            let (name, inline) = arg.split_once('=').map_or((arg.as_str(), None), |(name, v)| (name, Some(v)));
+            if arg == "-h" || arg == "--help" {
+                return Err(CliError::Help);
+            } else if let Some(switch) = SWITCHES.iter().find(|s| **s == arg) {
+                flags.switches.push(switch);
+            } else if let Some(flag) = VALUE_FLAGS.iter().find(|f| **f == name) {
+                let value = match inline {
+                    Some(v) => v.to_string(),
+                    None => it.next().cloned().ok_or_else(|| CliError::Invalid(format!("{arg} needs a value")))?,
+                };
+                flags.values.push((flag, value));
+            } else if arg.starts_with('-') {
+                return Err(CliError::Invalid(format!("unknown option {arg}")));
+            } else {
+                flags.words.push(arg.clone());
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Every value given for `flag`, in order.
+    pub fn all<'a>(&'a self, flag: &'a str) -> impl Iterator<Item = &'a str> {
+        self.values.iter().filter(move |(f, _)| *f == flag).map(|(_, v)| v.as_str())
+    }
+
+    /// The last value given for `flag`; later flags win.
+    pub fn last(&self, flag: &str) -> Option<&str> {
+        self.values.iter().rev().find(|(f, _)| *f == flag).map(|(_, v)| v.as_str())
+    }
+
+    pub fn has(&self, switch: &str) -> bool {
+        self.switches.contains(&switch)
+    }
+}
+
+/// Process-level options, parsed once. Stop conditions (`--duration` included) are built here;
+/// tuning (`--config`, `--set`) and HTTP (`--http-*`, `--cors-origin`) flags stay in `flags` for
+/// TuningConfig and HttpConfig, which also read files and the environment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub command: Command,
+    pub seed: u64,
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub output_dir: PathBuf,
+    /// Capture frames and encode the MP4; off for --no-video, VIDEO_EXPORT=0 and windowed runs
+    pub video: bool,
+    pub windowed: bool,
+    /// Seeds for `sweep`
+    pub seeds: Vec<u64>,
+    /// Start the HTTP server: always for `run`, otherwise only when an address is given
+    pub http: bool,
+    pub stop: StopConditions,
+    pub flags: Flags,
+}
+
+impl Cli {
+    pub fn parse(args: &[String]) -> Result<Self, CliError> {
+        Self::parse_with_env(args, |key| std::env::var(key).ok())
+    }
+
+    /// `args` includes the program name, as from `std::env::args()`.
+    pub fn parse_with_env(args: &[String], env: impl Fn(&str) -> Option<String>) -> Result<Self, CliError> {
+        let invalid = |reason: String| CliError::Invalid(reason);
+        let flags = Flags::parse(args)?;
+        let command = match flags.words.as_slice() {
+            [] => Command::Run,
+            [word] => match word.as_str() {
+                "run" => Command::Run,
+                "render" => Command::Render,
+                "sweep" => Command::Sweep,
+                other => return Err(invalid(format!("unknown command {other:?}"))),
+            },
+            [_, extra, ..] => return Err(invalid(format!("unexpected argument {extra:?}"))),
+        };
+
+        // Last flag wins, then the environment
+        let value = |flag: &str, var: &str| flags.last(flag).map(str::to_string).or_else(|| env(var)).filter(|v| !v.is_empty());
+
+        let seed = match value("--seed", "SEED") {
+            Some(raw) => raw.parse().map_err(|_| invalid(format!("seed must be a whole number, got {raw:?}")))?,
+            None => DEFAULT_SEED,
+        };
+        let (width, height) = match value("--resolution", "VIDEO_RESOLUTION") {
+            Some(raw) => parse_resolution(&raw).map_err(invalid)?,
+            None => (1080, 1920),
+        };
+        let fps = match value("--fps", "VIDEO_FPS") {
+            Some(raw) => raw.parse().ok().filter(|fps| *fps > 0).ok_or_else(|| invalid(format!("fps must be a positive whole number, got {raw:?}")))?,
+            None => 60,
+        };
+        let output_dir = PathBuf::from(value("--output-dir", "OUTPUT_DIR").unwrap_or_else(|| "./output".to_string()));
+        let duration = match value("--duration", "RUN_DURATION") {
+            Some(raw) => Some(
+                raw.parse::<f64>()
+                    .ok()
+                    .filter(|d| d.is_finite() && *d > 0.0)
+                    .ok_or_else(|| invalid(format!("duration must be a positive number of seconds, got {raw:?}")))?,
+            ),
+            None => None,
+        };
+        let seeds = match value("--seeds", "SWEEP_SEEDS") {
+            Some(raw) => parse_seeds(&raw).map_err(invalid)?,
+            None => Vec::new(),
+        };
+        let windowed = flags.has("--windowed") || env("WINDOWED").is_some();
+        let video_env = env("VIDEO_EXPORT").is_none_or(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "0" | "false" | "no" | "off"));
+        let video = !windowed && !flags.has("--no-video") && video_env;
+        let http = command == Command::Run || value("--http-addr", "HTTP_ADDR").is_some();
+        let mut stop = StopConditions::load_with_env(&flags, &env).map_err(invalid)?;
+        // Video time is counted in rendered frames
+        stop.max_video_frames = duration.map(|d| (d * fps as f64).round().max(1.0) as u64);
+
+        if windowed && command != Command::Run {
+            return Err(invalid("--windowed only applies to run".to_string()));
+        }
+        if command == Command::Sweep {
+            if seeds.is_empty() {
+                return Err(invalid("sweep needs --seeds".to_string()));
+            }
+            if flags.last("--http-addr").is_some() {
+                return Err(invalid("sweep renders run without HTTP, so --http-addr does not apply".to_string()));
+            }
+            // Extinction and the population limits may never trigger, so one of these must bound each render
+            if stop.max_video_frames.is_none() && stop.max_frames.is_none() && stop.max_sim_seconds.is_none() {
+                return Err(invalid("sweep needs --duration, --max-frames or --max-sim-seconds, or its first render would never end".to_string()));
+            }
+        }
+        Ok(Self { command, seed, width, height, fps, output_dir, video, windowed, seeds, http, stop, flags })
+    }
+
+    /// Arguments for the `render` child that runs one seed of a sweep. The child gets no HTTP
+    /// flags; run_sweep also keeps [`SWEEP_CHILD_UNSET_ENV`] from it.
+    pub fn sweep_child_args(&self, seed: u64, output_dir: &std::path::Path) -> Vec<String> {
+        let mut out = vec!["render".to_string()];
+        // Tuning and stop-condition flags pass through unchanged
+        for flag in ["--config", "--set", "--duration", "--max-frames", "--max-sim-seconds", "--max-population", "--dominance"] {
+            for v in self.flags.all(flag) {
+                out.extend([flag.to_string(), v.to_string()]);
+            }
+        }
+        out.extend([
+            "--seed".to_string(),
+            seed.to_string(),
+            "--output-dir".to_string(),
+            output_dir.display().to_string(),
+            "--resolution".to_string(),
+            format!("{}x{}", self.width, self.height),
+            "--fps".to_string(),
+            self.fps.to_string(),
+        ]);
+        if !self.video {
+            out.push("--no-video".to_string());
+        }
+        if self.flags.has("--stop-on-extinction") {
+            out.push("--stop-on-extinction".to_string());
+        }
+        out
+    }
+}
+
+// Even sizes only: libx264 with yuv420p rejects odd dimensions.
+fn parse_resolution(raw: &str) -> Result<(u32, u32), String> {
+    let bad = || format!("resolution must look like 1080x1920 with even sides, got {raw:?}");
+    let (w, h) = raw.split_once(['x', 'X']).ok_or_else(bad)?;
+    let (w, h): (u32, u32) = (w.trim().parse().map_err(|_| bad())?, h.trim().parse().map_err(|_| bad())?);
+    if w == 0 || h == 0 || w % 2 == 1 || h % 2 == 1 {
+        return Err(bad());
+    }
+    Ok((w, h))
+}
+
+// `1,2,5` or `1..8` (inclusive), or a mix: `1..3,10`.
+fn parse_seeds(raw: &str) -> Result<Vec<u64>, String> {
+    let bad = || format!("seeds must look like 1,2,3 or 1..8, got {raw:?}");
+    let mut seeds = Vec::new();
+    for part in raw.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        if let Some((a, b)) = part.split_once("..") {
+            let (a, b): (u64, u64) = (a.parse().map_err(|_| bad())?, b.parse().map_err(|_| bad())?);
+            if a > b || b - a >= 10_000 {
+                return Err(bad());
+            }
+            seeds.extend(a..=b);
+        } else {
+            seeds.push(part.parse().map_err(|_| bad())?);
+        }
+    }
+    Ok(seeds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str, env: &[(&str, &str)]) -> Result<Cli, CliError> {
+        let args: Vec<String> = std::iter::once("live-whirl").chain(args.split_whitespace()).map(str::to_string).collect();
+        Cli::parse_with_env(&args, |key| env.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string()))
+    }
+
+    #[test]
+    fn flags_beat_env_and_env_beats_defaults() {
+        let cli = parse("", &[]).unwrap();
+        assert_eq!((cli.command, cli.seed, cli.width, cli.height, cli.fps), (Command::Run, DEFAULT_SEED, 1080, 1920, 60));
+        assert!(cli.video && cli.http && cli.stop.is_empty());
+
+        let env = [("SEED", "7"), ("VIDEO_FPS", "30"), ("VIDEO_EXPORT", "0"), ("OUTPUT_DIR", "/tmp/out")];
+        let cli = parse("render --seed=9 --resolution 720x1280 --duration 2.5", &env).unwrap();
+        assert_eq!(cli.command, Command::Render);
+        assert_eq!((cli.seed, cli.width, cli.height, cli.fps), (9, 720, 1280, 30));
+        assert_eq!(cli.output_dir, PathBuf::from("/tmp/out"));
+        assert!(!cli.video && !cli.http);
+        assert_eq!(cli.stop.max_video_frames, Some(75));
+        assert_eq!(cli.flags.last("--resolution"), Some("720x1280"));
+        assert!(parse("render --http-addr 7900", &[]).unwrap().http);
+        assert!(parse("", &[("VIDEO_EXPORT", "1")]).unwrap().video);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(parse("--help", &[]), Err(CliError::Help));
+        assert_eq!(parse("sweep -h", &[]), Err(CliError::Help));
+        for bad in ["--bogus", "fly", "run extra", "--seed", "--seed x", "--resolution 1081x1920", "--fps 0", "--duration -1", "render --windowed", "sweep --duration 5", "sweep --seeds 1..3", "sweep --seeds 1 --duration 5 --http-addr 7900", "--max-frames 0"] {
+            assert!(matches!(parse(bad, &[]), Err(CliError::Invalid(_))), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn sweep_children_render_each_seed() {
+        let line = "sweep --seeds 1..3,10 --duration 4 --fps 30 --set bite_enabled=false --no-video --http-token t";
+        let cli = parse(line, &[]).unwrap();
+        assert_eq!(cli.seeds, vec![1, 2, 3, 10]);
+        assert!(parse("sweep --seeds 4 --max-frames 1000", &[]).is_ok());
+        let child = cli.sweep_child_args(2, std::path::Path::new("out/seed-2"));
+        let reparsed = Cli::parse_with_env(&std::iter::once("live-whirl".to_string()).chain(child.clone()).collect::<Vec<_>>(), |_| None).unwrap();
+        assert_eq!((reparsed.command, reparsed.seed, reparsed.fps, reparsed.video), (Command::Render, 2, 30, false));
+        assert_eq!(reparsed.output_dir, PathBuf::from("out/seed-2"));
+        assert!(child.windows(2).any(|w| w == ["--set", "bite_enabled=false"]));
+        assert_eq!(reparsed.stop.max_video_frames, Some(120));
+        assert!(!reparsed.http && !child.iter().any(|a| a.starts_with("--http")), "children run without HTTP");
+        assert!(SWEEP_CHILD_UNSET_ENV.contains(&"HTTP_ADDR"));
+        assert!(SWEEP_CHILD_UNSET_ENV.contains(&"EVENT_LOG"), "each seed logs events into its own directory");
+    }
+}
@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::cli::Flags;
+use crate::presets::{PresetError, PresetStore};
+use crate::tuning::{ApiTuning, ApiTuningUpdate, PhysicsTuning, TuningViolation};
+
+/// Prefix for per-field environment overrides, e.g. `TUNING_SURVIVAL_COST_PER_TICK=2`.
+pub const TUNING_ENV_PREFIX: &str = "TUNING_";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Preset(PresetError),
+    File { path: PathBuf, reason: String },
+    /// A `field=value` override from env or CLI that names no field or has the wrong type
+    Override { source: String, key: String, reason: String },
+    Invalid(Vec<TuningViolation>),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Preset(e) => write!(f, "{e}"),
+            Self::File { path, reason } => write!(f, "config file {}: {reason}", path.display()),
+            Self::Override { source, key, reason } => write!(f, "{source} override {key}: {reason}"),
+            Self::Invalid(violations) => {
+                write!(f, "invalid tuning:")?;
+                for v in violations {
+                    write!(f, "\n  {}: {}", v.field, v.reason)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Startup tuning built in layers: defaults (or a preset), then a config file, then env, then CLI.
+/// `sources` records which layers contributed, for the startup log.
+#[derive(Debug, Clone)]
+pub struct TuningConfig {
+    pub tuning: PhysicsTuning,
+    pub sources: Vec<String>,
+}
+
+impl Default for TuningConfig {
+    fn default() -> Self {
+        Self { tuning: PhysicsTuning::default(), sources: vec!["defaults".to_string()] }
+    }
+}
+
+impl TuningConfig {
+    /// Full chain for the binary: `TUNING_PRESET`, then `--config`/`TUNING_CONFIG`, then
+    /// `TUNING_<FIELD>` env vars, then `--set field=value` flags. The result is validated.
+    pub fn load(flags: &Flags) -> Result<Self, ConfigError> {
+        let mut cfg = Self::default();
+        if let Ok(name) = std::env::var("TUNING_PRESET") {
+            cfg = cfg.with_preset(&PresetStore::from_env(), &name)?;
+        }
+        let file = flags.last("--config").map(str::to_string).or_else(|| std::env::var("TUNING_CONFIG").ok());
+        if let Some(path) = file {
+            cfg = cfg.with_file(Path::new(&path))?;
+        }
+        cfg = cfg.with_env(std::env::vars())?;
+        cfg = cfg.with_overrides("cli", flags.all("--set"))?;
+        cfg.tuning.validate().map_err(ConfigError::Invalid)?;
+        Ok(cfg)
+    }
+
+    pub fn with_preset(mut self, store: &PresetStore, name: &str) -> Result<Self, ConfigError> {
+        self.tuning = store.load(name).map_err(ConfigError::Preset)?;
+        self.sources = vec![format!("preset {name} ({})", store.dir().display())];
+        Ok(self)
+    }
+
+    /// Merge a partial tuning file (same nested shape as PATCH /tuning); `.toml` files are TOML, anything else JSON.
+    pub fn with_file(mut self, path: &Path) -> Result<Self, ConfigError> {
+        let file_err = |reason: String| ConfigError::File { path: path.to_path_buf(), reason };
+        let text = std::fs::read_to_string(path).map_err(|e| file_err(e.to_string()))?;
+        let update: ApiTuningUpdate = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&text).map_err(|e| file_err(e.to_string()))?
+        } else {
+            serde_json::from_str(&text).map_err(|e| file_err(e.to_string()))?
+        };
+        update.apply_to(&mut self.tuning);
+        self.sources.push(format!("file {}", path.display()));
+        Ok(self)
+    }
+
+    /// Apply every `TUNING_<FIELD>` variable; the remainder of the name is the lowercased field.
+    pub fn with_env(self, vars: impl IntoIterator<Item = (String, String)>) -> Result<Self, ConfigError> {
+        let pairs: Vec<String> = vars
+            .into_iter()
+            .filter_map(|(k, v)| {
+                let field = k.strip_prefix(TUNING_ENV_PREFIX)?.to_ascii_lowercase();
+                // TUNING_PRESET and TUNING_CONFIG select layers rather than fields
+                (field != "preset" && field != "config").then(|| format!("{field}={v}"))
+            })
+            .collect();
+        self.with_overrides("env", pairs.iter().map(String::as_str))
+    }
+
+    /// Apply `field=value` pairs, where `field` is a flat `PhysicsTuning` field name.
+    pub fn with_overrides<'a>(mut self, source: &str, pairs: impl IntoIterator<Item = &'a str>) -> Result<Self, ConfigError> {
+        let mut applied = 0;
+        for pair in pairs {
+            let err = |key: &str, reason: String| ConfigError::Override { source: source.to_string(), key: key.to_string(), reason };
+            let Some((key, raw)) = pair.split_once('=') else {
+                return Err(err(pair, "expected field=value".to_string()));
+            };
+            let key = key.trim();
+            set_field(&mut self.tuning, key, raw.trim()).map_err(|reason| err(key, reason))?;
+            applied += 1;
+        }
+        if applied > 0 {
+            self.sources.push(format!("{source} ({applied})"));
+        }
+        Ok(self)
+    }
+
+    /// One-line summary plus the effective tuning as JSON, for the startup log.
+    pub fn describe(&self) -> String {
+        let json = serde_json::to_string(&ApiTuning::from(&self.tuning)).unwrap_or_default();
+        format!("sources=[{}] tuning={json}", self.sources.join(", "))
+    }
+}
+
+// Round-trip through JSON so every field is settable by name without listing them here.
+fn set_field(tuning: &mut PhysicsTuning, key: &str, raw: &str) -> Result<(), String> {
+    let mut value = serde_json::to_value(&*tuning).map_err(|e| e.to_string())?;
+    let fields = value.as_object_mut().ok_or("tuning is not an object")?;
+    let current = fields.get(key).ok_or_else(|| "unknown tuning field".to_string())?;
+    let parsed = match current {
+        Value::Bool(_) => raw.parse::<bool>().map(Value::Bool).map_err(|_| format!("expected true or false, got {raw:?}"))?,
+        Value::Number(n) if n.is_u64() && !raw.contains(['.', 'e', 'E']) => {
+            raw.parse::<u64>().map(Value::from).map_err(|_| format!("expected a non-negative integer, got {raw:?}"))?
+        }
+        _ => raw.parse::<f64>().map(Value::from).map_err(|_| format!("expected a number, got {raw:?}"))?,
+    };
+    fields.insert(key.to_string(), parsed);
+    *tuning = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layers_apply_in_order() {
+        let dir = std::env::temp_dir().join(format!("live-whirl-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("tuning.toml");
+        std::fs::write(&file, "[max_age]\nsurvival_cost_per_tick = 3\n[bite]\nbite_size_scale = 2.0\n").unwrap();
+
+        let cfg = TuningConfig::default()
+            .with_file(&file)
+            .unwrap()
+            .with_env([
+                ("TUNING_SURVIVAL_COST_PER_TICK".to_string(), "4".to_string()),
+                ("TUNING_PRESET".to_string(), "ignored".to_string()),
+                ("HOME".to_string(), "/root".to_string()),
+            ])
+            .unwrap()
+            .with_overrides("cli", ["bite_enabled=false"])
+            .unwrap();
+        assert_eq!(cfg.tuning.survival_cost_per_tick, 4); // env beats file
+        assert_eq!(cfg.tuning.bite_size_scale, 2.0); // file beats defaults
+        assert!(!cfg.tuning.bite_enabled);
+        assert_eq!(cfg.tuning.rel_vel_min, PhysicsTuning::default().rel_vel_min);
+        assert_eq!(cfg.sources.len(), 4);
+
+        assert!(TuningConfig::default().with_overrides("cli", ["no_such_field=1"]).is_err());
+        assert!(TuningConfig::default().with_overrides("cli", ["genome_max_age_min=1.5"]).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,135 @@
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use bevy::prelude::Resource;
+use futures_util::stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::events::SimEvent;
+use crate::tuning::AppState;
+
+/// One NDJSON line: `{"frame":..,"sim_seconds":..,"type":"ball_born",...}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    #[serde(flatten)]
+    pub event: SimEvent,
+}
+
+impl EventRecord {
+    /// The record as one NDJSON line, newline included.
+    pub fn to_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_default();
+        line.push('\n');
+        line
+    }
+}
+
+/// Fan-out of event records to GET /events/stream clients. Slow clients skip what they missed.
+#[derive(Resource, Clone)]
+pub struct EventFeed {
+    tx: broadcast::Sender<EventRecord>,
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(4096);
+        Self { tx }
+    }
+}
+
+impl EventFeed {
+    /// Dropped silently when nobody is listening.
+    pub fn publish(&self, record: EventRecord) {
+        let _ = self.tx.send(record);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<EventRecord> {
+        self.tx.subscribe()
+    }
+}
+
+/// How long appended lines may sit in the buffer before [`EventLogFile::flush_if_due`] writes them out.
+pub const EVENT_LOG_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The run's NDJSON event file, appended to as events happen. Lines are buffered and reach the
+/// disk once per [`EVENT_LOG_FLUSH_INTERVAL`] and on exit.
+#[derive(Resource)]
+pub struct EventLogFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    flushed: Instant,
+}
+
+impl EventLogFile {
+    /// Create (or truncate) the file, making its directory if needed.
+    pub fn create(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self { path, writer, flushed: Instant::now() })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append<'a>(&mut self, records: impl IntoIterator<Item = &'a EventRecord>) {
+        let result = records.into_iter().try_for_each(|r| self.writer.write_all(r.to_line().as_bytes()));
+        if let Err(e) = result {
+            eprintln!("[warn] event log {}: {e}", self.path.display());
+        }
+    }
+
+    /// Flush when the last flush is at least [`EVENT_LOG_FLUSH_INTERVAL`] old.
+    pub fn flush_if_due(&mut self) {
+        if self.flushed.elapsed() >= EVENT_LOG_FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// Write out everything buffered, so readers see whole lines.
+    pub fn flush(&mut self) {
+        self.flushed = Instant::now();
+        if let Err(e) = self.writer.flush() {
+            eprintln!("[warn] event log {}: {e}", self.path.display());
+        }
+    }
+}
+
+// Same lines as the file, from the moment the client connects.
+async fn event_stream(State(state): State<AppState>) -> Response {
+    let rx = state.events().subscribe();
+    let lines = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(record) => return Some((Ok::<_, Infallible>(record.to_line()), rx)),
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    eprintln!("[warn] /events/stream client lagged, skipped {n} events");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    ([(header::CONTENT_TYPE, "application/x-ndjson")], Body::from_stream(lines)).into_response()
+}
+
+pub fn event_routes() -> Router<AppState> {
+    Router::new().route("/events/stream", get(event_stream))
+}
@@ -0,0 +1,236 @@
+use bevy::prelude::{App, AppExit, Event, EventReader, FixedPostUpdate, Last, Plugin, Res, ResMut, Update, Vec2};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event_log::{EventFeed, EventLogFile, EventRecord},
+    sim::SimClock,
+};
+
+// Lifecycle events, written by the systems that make each change (see crate::ball and
+// crate::ball_systems) and logged as they are. Balls are named by their API id, so payloads mean
+// the same thing over HTTP and in logs; an id is `None` for a ball that had none yet, or for a peg on the other end of
+// a joint. Positions are in arena pixels.
+
+/// A ball appeared: seeded, budded from `parent`, or spawned through the API.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BallBorn {
+    pub id: u64,
+    /// The ball it budded from; `None` for seeded and API-spawned balls
+    pub parent: Option<u64>,
+    #[serde(flatten, with = "xy")]
+    pub position: Vec2,
+    pub life_points: u32,
+}
+
+/// Why a ball was removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeathCause {
+    /// Ran out of life points after passing its genome's max age
+    OldAge,
+    /// Ran out of life points before its max age (bites, draining pegs, joint upkeep)
+    Starved,
+    /// Fell through a floor drain in recycle mode
+    Drained,
+    /// Removed with DELETE /balls/{id}
+    Killed,
+}
+
+/// A ball was removed. World resets clear balls without reporting each one.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BallDied {
+    pub id: Option<u64>,
+    pub cause: DeathCause,
+    #[serde(flatten, with = "xy")]
+    pub position: Vec2,
+    /// In life ticks
+    pub age: u32,
+}
+
+/// Two balls stuck together.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointFormed {
+    pub a: Option<u64>,
+    pub b: Option<u64>,
+    /// Midpoint of the two balls
+    #[serde(flatten, with = "xy")]
+    pub position: Vec2,
+    /// Contact force that formed it, in Rapier units
+    pub force: f32,
+}
+
+/// A joint gave way under load; for a sticky peg anchor `a` is the peg, with no id. Joints that
+/// go with a dying ball are not reported.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointBroken {
+    pub a: Option<u64>,
+    pub b: Option<u64>,
+    #[serde(flatten, with = "xy")]
+    pub position: Vec2,
+    pub impulse: f32,
+    /// Break threshold after fatigue
+    pub threshold: f32,
+    /// `None` for peg anchors, which do not age
+    pub age_frames: Option<u64>,
+}
+
+/// `biter` took `amount` life points from `victim` in a hostile collision.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bitten {
+    pub biter: Option<u64>,
+    pub victim: Option<u64>,
+    pub amount: u32,
+    #[serde(flatten, with = "xy")]
+    pub position: Vec2,
+    pub force: f32,
+}
+
+/// Any one lifecycle event, tagged with its `type` when logged: `{"type":"ball_died","id":57,...}`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SimEvent {
+    BallBorn(BallBorn),
+    BallDied(BallDied),
+    JointFormed(JointFormed),
+    JointBroken(JointBroken),
+    Bitten(Bitten),
+}
+
+// Positions are logged as flat `x` and `y` fields beside the rest of the payload.
+mod xy {
+    use bevy::prelude::Vec2;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Xy {
+        x: f32,
+        y: f32,
+    }
+
+    pub fn serialize<S: Serializer>(position: &Vec2, serializer: S) -> Result<S::Ok, S::Error> {
+        Xy { x: position.x, y: position.y }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+        Xy::deserialize(deserializer).map(|Xy { x, y }| Vec2::new(x, y))
+    }
+}
+
+// Stamp every event with the physics step it was read in and hand it to the NDJSON file and
+// /events/stream. Runs after each step; API changes made while paused are stamped at the next one.
+#[allow(clippy::too_many_arguments)]
+fn record_sim_events(
+    clock: Option<Res<SimClock>>,
+    file: Option<ResMut<EventLogFile>>,
+    feed: Option<Res<EventFeed>>,
+    mut born: EventReader<BallBorn>,
+    mut died: EventReader<BallDied>,
+    mut formed: EventReader<JointFormed>,
+    mut broken: EventReader<JointBroken>,
+    mut bitten: EventReader<Bitten>,
+) {
+    let (frame, sim_seconds) = clock.map_or((0, 0.0), |c| (c.frame(), c.seconds()));
+    let events = born
+        .read()
+        .copied()
+        .map(SimEvent::BallBorn)
+        .chain(died.read().copied().map(SimEvent::BallDied))
+        .chain(formed.read().copied().map(SimEvent::JointFormed))
+        .chain(broken.read().copied().map(SimEvent::JointBroken))
+        .chain(bitten.read().copied().map(SimEvent::Bitten));
+    let records: Vec<EventRecord> = events.map(|event| EventRecord { frame, sim_seconds, event }).collect();
+    if let Some(mut file) = file {
+        file.append(&records);
+    }
+    if let Some(feed) = feed {
+        for record in records {
+            feed.publish(record);
+        }
+    }
+}
+
+// Every frame, so lines still reach the disk while the simulation is paused.
+fn flush_event_log_if_due(file: Option<ResMut<EventLogFile>>) {
+    if let Some(mut file) = file {
+        file.flush_if_due();
+    }
+}
+
+// The frame the app exits on, after its last step has been recorded.
+fn flush_event_log_on_exit(mut exit: EventReader<AppExit>, file: Option<ResMut<EventLogFile>>) {
+    if exit.read().count() > 0 {
+        if let Some(mut file) = file {
+            file.flush();
+        }
+    }
+}
+
+pub struct SimEventsPlugin;
+
+impl Plugin for SimEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BallBorn>()
+            .add_event::<BallDied>()
+            .add_event::<JointFormed>()
+            .add_event::<JointBroken>()
+            .add_event::<Bitten>()
+            .add_systems(FixedPostUpdate, record_sim_events)
+            .add_systems(Update, flush_event_log_if_due)
+            .add_systems(Last, flush_event_log_on_exit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::Events;
+
+    use super::*;
+
+    #[test]
+    fn events_reach_the_feed_stamped_with_the_step() {
+        let mut app = App::new();
+        let clock = SimClock::default();
+        clock.set(12, 0.2);
+        let feed = EventFeed::default();
+        let mut rx = feed.subscribe();
+        app.insert_resource(clock).insert_resource(feed).add_plugins(SimEventsPlugin);
+
+        app.world_mut().resource_mut::<Events<BallDied>>().send(BallDied { id: None, cause: DeathCause::Starved, position: Vec2::ZERO, age: 3 });
+        app.world_mut().resource_mut::<Events<JointBroken>>().send(JointBroken {
+            a: None,
+            b: Some(4),
+            position: Vec2::new(1.0, 2.0),
+            impulse: 9.0,
+            threshold: 5.0,
+            age_frames: None,
+        });
+        app.world_mut().run_schedule(FixedPostUpdate);
+
+        let died = rx.try_recv().unwrap();
+        assert_eq!((died.frame, died.sim_seconds), (12, 0.2));
+        assert_eq!(died.event, SimEvent::BallDied(BallDied { id: None, cause: DeathCause::Starved, position: Vec2::ZERO, age: 3 }));
+        let broken = rx.try_recv().unwrap();
+        let line: serde_json::Value = serde_json::from_str(&broken.to_line()).unwrap();
+        assert_eq!((line["type"].as_str(), line["a"].is_null(), line["b"].as_u64()), (Some("joint_broken"), true, Some(4)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn exit_flushes_the_event_log() {
+        let dir = std::env::temp_dir().join(format!("live-whirl-event-exit-{}", std::process::id()));
+        let path = dir.join("run.events.ndjson");
+        let mut app = App::new();
+        app.insert_resource(EventLogFile::create(&path).unwrap()).add_event::<AppExit>().add_plugins(SimEventsPlugin);
+
+        app.world_mut().resource_mut::<Events<BallDied>>().send(BallDied { id: Some(7), cause: DeathCause::Killed, position: Vec2::ZERO, age: 1 });
+        app.world_mut().run_schedule(FixedPostUpdate);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "", "lines stay buffered until a flush");
+
+        app.world_mut().send_event(AppExit::Success);
+        app.world_mut().run_schedule(Last);
+        let logged = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        let line: serde_json::Value = serde_json::from_str(logged.trim_end()).unwrap();
+        assert_eq!((line["type"].as_str(), line["id"].as_u64()), (Some("ball_died"), Some(7)));
+    }
+}
@@ -1,32 +1,43 @@
 use std::env;
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::mpsc::Receiver;
 use std::thread;
 
+use crate::metrics::CaptureCounters;
+
 pub struct FfmpegHandle {
     pub child: Child,
 }
 
+/// Where a recording started now goes: `<output_dir>/video/<fps>_<utc time>.mp4`.
+/// Other run outputs take the same stem with their own extension.
+pub fn recording_path(output_dir: &Path, fps: u32) -> PathBuf {
+    output_dir
+        .join("video")
+        .join(format!("{}_{}.mp4", fps, chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S")))
+}
+
+/// Encode frames from `rx` to `path`, plus the UDP preview when `preview` is set.
 pub fn spawn_ffmpeg(
+    path: &Path,
     width: u32,
     height: u32,
     fps: u32,
+    preview: bool,
     rx: Receiver<Vec<u8>>,
+    counters: CaptureCounters,
 ) -> std::io::Result<FfmpegHandle> {
     // Ensure output directory exists
-    let out_dir = Path::new("./output/video");
-    if let Err(e) = fs::create_dir_all(out_dir) {
-        eprintln!("[warn] could not create {:?}: {e}", out_dir);
+    if let Some(out_dir) = path.parent() {
+        if let Err(e) = fs::create_dir_all(out_dir) {
+            eprintln!("[warn] could not create {:?}: {e}", out_dir);
+        }
     }
 
-    let filename = format!(
-        "./output/video/{}_{}.mp4",
-        fps,
-        chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S")
-    );
+    let filename = path.display().to_string();
     // Write to MP4 (faststart) and a UDP MPEG-TS preview simultaneously via tee
     // UDP host/port configurable via env UDP_HOST and UDP_PORT; defaults 127.0.0.1:12345.
     // UDP_HOST may be a comma-separated list of IPs to broadcast to multiple endpoints.
@@ -39,6 +50,7 @@ pub fn spawn_ffmpeg(
     let udp_port = env::var("UDP_PORT").unwrap_or_else(|_| "12345".to_string());
     // Build tee outputs: MP4 file and preview sinks (override via PREVIEW_URL)
     let preview_urls: Vec<String> = match env::var("PREVIEW_URL") {
+        _ if !preview => Vec::new(),
         Ok(url) => vec![url],
         Err(_) => udp_hosts
             .iter()
@@ -47,20 +59,21 @@ pub fn spawn_ffmpeg(
     };
 
     fn make_tee_outputs(file: &str, previews: &[String]) -> String {
-        let preview_sinks = previews
-            .iter()
-            .map(|preview| {
-                format!(
-                    "[f=mpegts:onfail=ignore:mpegts_flags=+resend_headers+initial_discontinuity:flush_packets=1]{preview}",
-                    preview = preview
-                )
-            })
+        let preview_sinks = previews.iter().map(|preview| {
+            format!(
+                "[f=mpegts:onfail=ignore:mpegts_flags=+resend_headers+initial_discontinuity:flush_packets=1]{preview}",
+                preview = preview
+            )
+        });
+        std::iter::once(format!("[f=mp4:movflags=+faststart]{file}"))
+            .chain(preview_sinks)
             .collect::<Vec<_>>()
-            .join("|");
-        format!("[f=mp4:movflags=+faststart]{file}|{preview_sinks}")
+            .join("|")
     }
     let tee_outputs = make_tee_outputs(&filename, &preview_urls);
-    eprintln!("[diag] preview tee sink: {}", preview_urls.join(" | "));
+    if preview {
+        eprintln!("[diag] preview tee sink: {}", preview_urls.join(" | "));
+    }
 
     let mut child = Command::new("ffmpeg")
         .args([
@@ -117,7 +130,9 @@ pub fn spawn_ffmpeg(
 
     thread::spawn(move || {
         while let Ok(frame) = rx.recv() {
+            counters.written();
             if frame.len() != expected {
+                counters.dropped(1);
                 eprintln!(
                     "[diag] bad frame size {} (expected {})",
                     frame.len(),
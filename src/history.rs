@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::tuning::PhysicsTuning;
+
+/// One field that changed, by its flat `PhysicsTuning` name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// One applied tuning change.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningChange {
+    pub id: u64,
+    /// RFC 3339, UTC
+    pub timestamp: String,
+    pub frame: u64,
+    pub sim_seconds: f64,
+    /// What made the change: "patch", "preset <name>", "undo <id>", "redo <id>", ...
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub diff: Vec<FieldChange>,
+}
+
+/// Append-only log of tuning changes plus undo/redo stacks over it.
+/// Undo and redo are themselves logged, so the log always reads as what actually happened.
+#[derive(Debug, Default)]
+pub struct TuningHistory {
+    log: Vec<TuningChange>,
+    // Indices into `log` of changes that can be undone / redone
+    undo: Vec<usize>,
+    redo: Vec<usize>,
+    persist_path: Option<PathBuf>,
+}
+
+/// Where a change happened, for stamping log entries.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChangeStamp {
+    pub frame: u64,
+    pub sim_seconds: f64,
+}
+
+impl TuningHistory {
+    /// Rewrite the log to `path` (as JSON) after every change.
+    pub fn persist_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.persist_path = Some(path.into());
+        self
+    }
+
+    pub fn entries(&self) -> &[TuningChange] {
+        &self.log
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Record an ordinary change. No-op changes are not logged. Clears the redo stack.
+    pub fn record(
+        &mut self,
+        before: &PhysicsTuning,
+        after: &PhysicsTuning,
+        source: &str,
+        note: Option<String>,
+        stamp: ChangeStamp,
+    ) -> Option<&TuningChange> {
+        let diff = diff_tuning(before, after);
+        if diff.is_empty() {
+            return None;
+        }
+        self.push(diff, source.to_string(), note, stamp);
+        self.undo.push(self.log.len() - 1);
+        self.redo.clear();
+        self.log.last()
+    }
+
+    /// The tuning that undoing the latest change would produce, without committing it.
+    pub fn peek_undo(&self, current: &PhysicsTuning) -> Option<PhysicsTuning> {
+        let idx = *self.undo.last()?;
+        Some(with_values(current, self.log[idx].diff.iter().map(|c| (c.field.clone(), c.before.clone()))))
+    }
+
+    /// The tuning that redoing the latest undone change would produce, without committing it.
+    pub fn peek_redo(&self, current: &PhysicsTuning) -> Option<PhysicsTuning> {
+        let idx = *self.redo.last()?;
+        Some(with_values(current, self.log[idx].diff.iter().map(|c| (c.field.clone(), c.after.clone()))))
+    }
+
+    /// Commit an undo previously previewed with `peek_undo`.
+    pub fn commit_undo(&mut self, before: &PhysicsTuning, after: &PhysicsTuning, note: Option<String>, stamp: ChangeStamp) {
+        let Some(idx) = self.undo.pop() else { return; };
+        let source = format!("undo {}", self.log[idx].id);
+        self.push(diff_tuning(before, after), source, note, stamp);
+        self.redo.push(idx);
+    }
+
+    /// Commit a redo previously previewed with `peek_redo`.
+    pub fn commit_redo(&mut self, before: &PhysicsTuning, after: &PhysicsTuning, note: Option<String>, stamp: ChangeStamp) {
+        let Some(idx) = self.redo.pop() else { return; };
+        let source = format!("redo {}", self.log[idx].id);
+        self.push(diff_tuning(before, after), source, note, stamp);
+        self.undo.push(idx);
+    }
+
+    fn push(&mut self, diff: Vec<FieldChange>, source: String, note: Option<String>, stamp: ChangeStamp) {
+        let id = self.log.last().map_or(1, |c| c.id + 1);
+        self.log.push(TuningChange {
+            id,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            frame: stamp.frame,
+            sim_seconds: stamp.sim_seconds,
+            source,
+            note,
+            diff,
+        });
+        self.persist();
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.persist_path else { return; };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        match serde_json::to_string_pretty(&self.log) {
+            Ok(text) => {
+                if let Err(e) = std::fs::write(path, text) {
+                    eprintln!("[warn] tuning history {}: {e}", path.display());
+                }
+            }
+            Err(e) => eprintln!("[warn] tuning history: {e}"),
+        }
+    }
+}
+
+fn fields_of(t: &PhysicsTuning) -> Map<String, Value> {
+    match serde_json::to_value(t) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Fields whose values differ, sorted by field name.
+pub fn diff_tuning(before: &PhysicsTuning, after: &PhysicsTuning) -> Vec<FieldChange> {
+    let before = fields_of(before);
+    let after = fields_of(after);
+    after
+        .iter()
+        .filter_map(|(field, new)| {
+            let old = before.get(field).cloned().unwrap_or(Value::Null);
+            (old != *new).then(|| FieldChange { field: field.clone(), before: old, after: new.clone() })
+        })
+        .collect()
+}
+
+/// Overwrite just the named fields, leaving the rest of `current` alone.
+/// Falls back to `current` unchanged if a value does not fit its field.
+pub fn with_values(current: &PhysicsTuning, values: impl IntoIterator<Item = (String, Value)>) -> PhysicsTuning {
+    let mut fields = fields_of(current);
+    for (field, value) in values {
+        fields.insert(field, value);
+    }
+    serde_json::from_value(Value::Object(fields)).unwrap_or_else(|_| current.clone())
+}
@@ -0,0 +1,119 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use crate::cli::Flags;
+
+pub const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:7878";
+
+/// How the tuning server listens and who may use it.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub addr: SocketAddr,
+    /// When set, mutating requests need `Authorization: Bearer <token>`; reads stay open
+    pub token: Option<String>,
+    /// Origins allowed by CORS; `*` allows any. Empty disables CORS headers.
+    pub cors_origins: Vec<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self { addr: DEFAULT_HTTP_ADDR.parse().unwrap(), token: None, cors_origins: Vec::new() }
+    }
+}
+
+impl HttpConfig {
+    /// `--http-addr`, `--http-token` and `--cors-origin` (repeatable), falling back to
+    /// `HTTP_ADDR`, `HTTP_TOKEN` and `HTTP_CORS_ORIGINS` (comma separated).
+    pub fn load(flags: &Flags) -> Result<Self, String> {
+        let mut cfg = Self::default();
+        if let Some(raw) = flags.last("--http-addr").map(str::to_string).or_else(|| std::env::var("HTTP_ADDR").ok()) {
+            cfg.addr = parse_addr(&raw)?;
+        }
+        cfg.token = flags.last("--http-token").map(str::to_string).or_else(|| std::env::var("HTTP_TOKEN").ok()).filter(|t| !t.is_empty());
+        let mut origins: Vec<String> = flags.all("--cors-origin").map(str::to_string).collect();
+        if origins.is_empty() {
+            origins = std::env::var("HTTP_CORS_ORIGINS").map(|s| s.split(',').map(str::to_string).collect()).unwrap_or_default();
+        }
+        cfg.cors_origins = origins.into_iter().map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect();
+        for origin in &cfg.cors_origins {
+            if origin != "*" {
+                HeaderValue::from_str(origin).map_err(|_| format!("invalid CORS origin {origin:?}"))?;
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// Wrap a router with token auth and CORS. CORS is outermost so preflights never need a token.
+    pub fn layer(&self, router: Router) -> Router {
+        let mut router = router;
+        if let Some(token) = &self.token {
+            let expected: Arc<str> = Arc::from(format!("Bearer {token}"));
+            router = router.layer(middleware::from_fn_with_state(expected, require_token));
+        }
+        if !self.cors_origins.is_empty() {
+            let origins = if self.cors_origins.iter().any(|o| o == "*") {
+                AllowOrigin::any()
+            } else {
+                AllowOrigin::list(self.cors_origins.iter().filter_map(|o| HeaderValue::from_str(o).ok()))
+            };
+            router = router.layer(CorsLayer::new().allow_origin(origins).allow_methods(Any).allow_headers(Any));
+        }
+        router
+    }
+}
+
+// Bare ports bind on localhost, so several instances can share a host with just `--http-addr 7879`.
+fn parse_addr(raw: &str) -> Result<SocketAddr, String> {
+    if let Ok(addr) = raw.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    match raw.trim_start_matches(':').parse::<u16>() {
+        Ok(port) => Ok(SocketAddr::from(([127, 0, 0, 1], port))),
+        Err(_) => Err(format!("invalid http address {raw:?} (use host:port or a port)")),
+    }
+}
+
+/// Body of a `401`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthError {
+    pub error: String,
+}
+
+async fn require_token(State(expected): State<Arc<str>>, req: Request, next: Next) -> Response {
+    let read_only = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        // Constant time, so response timing does not leak how much of a guess matched
+        .is_some_and(|v| bool::from(v.as_bytes().ct_eq(expected.as_bytes())));
+    if read_only || authorized {
+        return next.run(req).await;
+    }
+    let body = AuthError { error: "missing or wrong bearer token".to_string() };
+    (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, "Bearer")], Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_addresses_and_ports() {
+        assert_eq!(parse_addr("0.0.0.0:9000").unwrap(), SocketAddr::from(([0, 0, 0, 0], 9000)));
+        assert_eq!(parse_addr("7879").unwrap(), SocketAddr::from(([127, 0, 0, 1], 7879)));
+        assert_eq!(parse_addr(":7880").unwrap(), SocketAddr::from(([127, 0, 0, 1], 7880)));
+        assert!(parse_addr("localhost").is_err());
+    }
+}
@@ -1,2 +1,19 @@
+pub mod arena;
+pub mod balls;
+pub mod cli;
+pub mod config;
+pub mod event_log;
+pub mod events;
+pub mod history;
+pub mod http;
+pub mod metrics;
+pub mod panel;
+pub mod pegs_api;
+pub mod presets;
+pub mod query;
+pub mod schedule;
+pub mod shared_consts;
+pub mod sim;
+pub mod stream;
+pub mod termination;
 pub mod tuning;
-
@@ -8,6 +8,8 @@ use bevy::{
     color::Color,
     prelude::{
         App,
+        FixedUpdate,
+        IntoScheduleConfigs,
         Update,
         Events,
         AppExit,
@@ -21,40 +23,106 @@ use bevy::{
     },
     window::WindowPlugin,
 };
+use bevy_rapier2d::prelude::PhysicsSet;
 
 
 
 
+mod arena;
 mod ball;
+mod ball_systems;
+mod balls;
 mod capture;
+mod cli;
+mod config;
+mod event_log;
+mod events;
 mod ffmpeg;
+mod history;
+mod http;
+mod panel;
 mod setup;
 mod shared_consts;
+mod sim;
+mod stats;
+mod stop;
+mod stream;
 mod markers;
+mod metrics;
+mod pegs;
+mod pegs_api;
+mod presets;
+mod query;
+mod query_systems;
+mod schedule;
+mod termination;
 mod tuning;
 
 #[derive(Clone, bevy::prelude::Resource)]
 struct AllowExitFlag(std::sync::Arc<std::sync::atomic::AtomicBool>);
 
 use crate::{
+    pegs_api::PegsApi,
     ball::BallPlugin,
+    ball_systems::BallSystemsPlugin,
+    balls::BallsApi,
+    pegs::PegPlugin,
+    presets::PresetStore,
+    query::QueryApi,
+    query_systems::QuerySystemsPlugin,
     capture::{ add_render_capture_systems, FrameSender },
-    ffmpeg::{ spawn_ffmpeg, FfmpegHandle },
-    setup::{ SetupPlugin, VideoExportRequest },
-    tuning::{ spawn_axum_server, PhysicsTuning, TuningRx, TuningMirror },
+    cli::{ Cli, CliError, Command, SWEEP_CHILD_UNSET_ENV },
+    config::TuningConfig,
+    event_log::{EventFeed, EventLogFile},
+    events::SimEventsPlugin,
+    history::TuningHistory,
+    metrics::Metrics,
+    http::HttpConfig,
+    ffmpeg::{ recording_path, spawn_ffmpeg, FfmpegHandle },
+    setup::{ RngResource, SetupPlugin, SimTiming, VideoExportRequest },
+    sim::{ SimApi, SimClock },
+    stats::StatsPlugin,
+    stop::StopPlugin,
+    stream::LiveFeed,
+    termination::{ ExitReason, ExitRecord, RunOutcome },
+    tuning::{ spawn_axum_server, AppState, TuningRx, TuningMirror },
 };
 
 fn main() {
-    // Default: headless video recording with UDP preview
-    // Optional: windowed/interactive via --windowed flag or WINDOWED=1 env var
-    let windowed = std::env::args().any(|a| a == "--windowed")
-        || std::env::var("WINDOWED").ok().is_some();
+    // Default: `run`, headless video recording with UDP preview; see `--help` (cli::HELP)
+    let args: Vec<String> = std::env::args().collect();
+    let cli = match Cli::parse(&args) {
+        Ok(cli) => cli,
+        Err(CliError::Help) => {
+            print!("{}", cli::HELP);
+            return;
+        }
+        Err(e) => {
+            eprintln!("[error] {e}");
+            std::process::exit(2);
+        }
+    };
+    if cli.command == Command::Sweep {
+        std::process::exit(run_sweep(&cli));
+    }
+    let windowed = cli.windowed;
+
+    // Defaults (or TUNING_PRESET), then config file, env and CLI overrides
+    let tuning_config = TuningConfig::load(&cli.flags).unwrap_or_else(|e| {
+        eprintln!("[error] {e}");
+        std::process::exit(2);
+    });
+    eprintln!("[diag] effective tuning {}", tuning_config.describe());
+    let http_config = HttpConfig::load(&cli.flags).unwrap_or_else(|e| {
+        eprintln!("[error] {e}");
+        std::process::exit(2);
+    });
 
     let mut app = App::new();
     app.insert_resource(ClearColor(Color::srgba(0.17, 0.18, 0.19, 1.0)));
 
     // Single source of truth for FPS
-    let fps: u32 = std::env::var("VIDEO_FPS").ok().and_then(|s| s.parse().ok()).unwrap_or(60);
+    let fps = cli.fps;
 
     if windowed {
         // Standard windowed stack
@@ -73,7 +141,12 @@ fn main() {
                 .set(WindowPlugin { primary_window: None, ..Default::default() })
                 .disable::<WinitPlugin>(),
         );
-        app.add_plugins(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(1.0 / fps as f64)));
+        // `render` runs flat out; time still advances one video frame per update (below)
+        let pace = if cli.command == Command::Render { Duration::ZERO } else { Duration::from_secs_f64(1.0 / fps as f64) };
+        app.add_plugins(ScheduleRunnerPlugin::run_loop(pace));
+        // Each rendered frame is exactly one video frame of time, however long it took to produce;
+        // SimTiming.speed then maps video seconds to simulated seconds.
+        app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / fps as f64)));
         // Disable GPU preprocessing to avoid Core3D prepass requirement (headless)
         if let Some(render_app) = app.get_sub_app_mut(bevy::render::RenderApp) {
             use bevy::render::batching::gpu_preprocessing::{GpuPreprocessingMode, GpuPreprocessingSupport};
@@ -81,10 +154,16 @@ fn main() {
         }
     }
 
+    // Shared with the capture bridge and the ffmpeg writer thread, served at GET /metrics
+    let metrics = Metrics::default();
+    app.insert_resource(metrics.clone());
+
     // Initialize export pipeline by default in headless mode and hold ffmpeg handle for post-exit wait()
-    let ff_handle: Option<FfmpegHandle> = if !windowed {
+    // Run outputs share this path's stem and directory whether or not video is on
+    let recording = recording_path(&cli.output_dir, fps);
+    let ff_handle: Option<FfmpegHandle> = if cli.video {
         // Provide export request; setup_graphics will create an offscreen target and camera
-        app.insert_resource(VideoExportRequest { width: 1080, height: 1920, fps });
+        app.insert_resource(VideoExportRequest { width: cli.width, height: cli.height });
 
         // Frame channel to feed ffmpeg
         let (tx, rx) = std::sync::mpsc::channel::<Vec<u8>>();
@@ -95,68 +174,92 @@ fn main() {
 
         // Spawn ffmpeg thread
         Some(
-            spawn_ffmpeg(1080, 1920, fps, rx)
+            spawn_ffmpeg(&recording, cli.width, cli.height, fps, cli.command == Command::Run, rx, metrics.capture.clone())
                 .expect("Failed to spawn ffmpeg; ensure it is installed and on PATH"),
         )
     } else {
         None
     };
 
-    // Core scene plugins
-    app.add_plugins(( SetupPlugin, BallPlugin ));
+    // Physics rate, substeps and speed are independent of the video fps
+    app.insert_resource(SimTiming::from_env(fps));
+
+    // Core scene plugins; SetupPlugin keeps a seeded RNG that is already there
+    app.insert_resource(RngResource::seeded(cli.seed));
+    // Whatever ends the run records why first; written next to the recording on exit
+    let outcome = RunOutcome::default();
+    app.insert_resource(outcome.clone());
+    app.add_plugins((
+        SetupPlugin,
+        SimEventsPlugin,
+        BallPlugin,
+        BallSystemsPlugin,
+        QuerySystemsPlugin,
+        PegPlugin,
+        StatsPlugin,
+        StopPlugin { conditions: cli.stop.clone() },
+    ));
 
     // Install tuning HTTP server (Axum) and channel bridge
-    use std::{net::SocketAddr, sync::{mpsc, Arc, Mutex}};
+    use std::sync::{mpsc, Arc, Mutex};
     let (tuning_tx, tuning_rx) = mpsc::channel();
-    let tuning_mirror = Arc::new(Mutex::new(PhysicsTuning {
-        rel_vel_min: 0.15,
-        rel_vel_max: 360.0,
-        break_force_threshold: 360.0,
-        contact_force_performance_cutoff_fudge_factor: 0.00001,
-        energy_transfer_enabled: true,
-        energy_share_diff_threshold: 100,
-        energy_share_friendly_rate: 0.5,
-        energy_share_parent_not_friendly_child_friendly_rate: 0.75,
-        energy_share_parent_friendly_child_not_friendly_rate: 0.25,
-        energy_share_hostile_rand_min: 0.5,
-        energy_share_hostile_rand_max: 0.9,
-        bite_enabled: true,
-        bite_size_scale: 1.0,
-        genome_bite_size_min: 0,
-        genome_bite_size_max: 400,
-        genome_energy_share_min: 0.25,
-        genome_energy_share_max: 0.75,
-        genome_friendly_distance_min: 0.15,
-        genome_friendly_distance_max: 1.0,
-        genome_friendly_scent_range: 1.0,
-        genome_max_age_min: 90,
-        genome_max_age_max: 120,
-        genome_reproduction_rate_min: 0.00625 * 1.9,
-        genome_reproduction_rate_max: 0.00625 * 2.0,
-        genome_safe_reproduction_points_min: 0,
-        genome_safe_reproduction_points_max: 1000,
-        survival_cost_per_tick: 1,
-        // Labels default off
-        show_collision_labels: false,
-        collision_label_force_min: 2.0,
-        show_break_labels: false,
-        break_label_impulse_min: 20.0,
-        // New label toggles/ranges
-        show_age_labels: false,
-        age_label_min: 0.0,
-        age_label_max: f32::MAX,
-        show_energy_labels: false,
-        energy_label_min: 0.0,
-        energy_label_max: f32::MAX,
-    }));
+    let tuning_mirror = Arc::new(Mutex::new(tuning_config.tuning.clone()));
     app.insert_non_send_resource(TuningRx(tuning_rx));
     app.insert_resource(TuningMirror(tuning_mirror.clone()));
-    spawn_axum_server(SocketAddr::from(([127,0,0,1], 7878)), tuning_tx, tuning_mirror);
+    let (pegs_api, pegs_rx, pegs_mirror) = PegsApi::channel();
+    app.insert_non_send_resource(pegs_rx);
+    app.insert_resource(pegs_mirror);
+    let (balls_api, balls_rx, balls_mirror, ball_ids) = BallsApi::channel();
+    app.insert_non_send_resource(balls_rx);
+    app.insert_resource(balls_mirror);
+    app.insert_resource(ball_ids);
+    let (query_api, query_rx) = QueryApi::channel();
+    app.insert_non_send_resource(query_rx);
+    let (sim_api, sim_rx, sim_mirror) = SimApi::channel();
+    app.insert_non_send_resource(sim_rx);
+    app.insert_resource(sim_mirror);
+    let sim_clock = SimClock::default();
+    app.insert_resource(sim_clock.clone());
+    let live_feed = LiveFeed::default();
+    app.insert_resource(live_feed.clone());
+    let event_feed = EventFeed::default();
+    app.insert_resource(event_feed.clone());
+    // Tuning history is saved next to the recording
+    let history = TuningHistory::default().persist_to(recording.with_extension("tuning-history.json"));
+    // EVENT_LOG overrides where lifecycle events are logged
+    let event_log_path = std::env::var_os("EVENT_LOG").map(std::path::PathBuf::from).unwrap_or_else(|| recording.with_extension("events.ndjson"));
+    match EventLogFile::create(&event_log_path) {
+        Ok(file) => {
+            eprintln!("[diag] event log -> {}", file.path().display());
+            app.insert_resource(file);
+        }
+        Err(e) => eprintln!("[warn] event log {}: {e}; events are only streamed", event_log_path.display()),
+    }
+    let server_state = AppState::new(tuning_tx, tuning_mirror)
+        .with_pegs(pegs_api)
+        .with_balls(balls_api)
+        .with_query(query_api)
+        .with_sim(sim_api)
+        .with_presets(PresetStore::new(cli.output_dir.join("presets")))
+        .with_history(history)
+        .with_clock(sim_clock.clone())
+        .with_feed(live_feed)
+        .with_events(event_feed)
+        .with_metrics(metrics);
+    app.insert_resource(server_state.clone());
+    if cli.http {
+        if let Err(e) = spawn_axum_server(&http_config, server_state) {
+            eprintln!("[error] cannot listen on {}: {e} (set --http-addr or HTTP_ADDR to use another address)", http_config.addr);
+            std::process::exit(2);
+        }
+    }
 
     // System to apply updates from HTTP
     app.add_systems(Update, tuning::apply_tuning_updates_system);
-    // Provide default tuning resource (so systems can read it)
-    app.insert_resource(PhysicsTuning { rel_vel_min: 0.15, rel_vel_max: 360.0, break_force_threshold: 360.0, contact_force_performance_cutoff_fudge_factor: 0.00001, energy_transfer_enabled: true, energy_share_diff_threshold: 100, energy_share_friendly_rate: 0.5, energy_share_parent_not_friendly_child_friendly_rate: 0.75, energy_share_parent_friendly_child_not_friendly_rate: 0.25, energy_share_hostile_rand_min: 0.5, energy_share_hostile_rand_max: 0.9, bite_enabled: true, bite_size_scale: 1.0, genome_bite_size_min: 0, genome_bite_size_max: 400, genome_energy_share_min: 0.25, genome_energy_share_max: 0.75, genome_friendly_distance_min: 0.15, genome_friendly_distance_max: 1.0, genome_friendly_scent_range: 1.0, genome_max_age_min: 90, genome_max_age_max: 120, genome_reproduction_rate_min: 0.00625 * 1.9, genome_reproduction_rate_max: 0.00625 * 2.0, genome_safe_reproduction_points_min: 0, genome_safe_reproduction_points_max: 1000, survival_cost_per_tick: 1, show_collision_labels: false, collision_label_force_min: 2.0, show_break_labels: false, break_label_impulse_min: 20.0, show_age_labels: false, age_label_min: 0.0, age_label_max: f32::MAX, show_energy_labels: false, energy_label_min: 0.0, energy_label_max: f32::MAX });
+    // Scheduled and ramped changes fire on simulation ticks
+    app.add_systems(FixedUpdate, schedule::run_tuning_schedule.before(PhysicsSet::SyncBackend));
+    // Provide the startup tuning resource (so systems can read it)
+    app.insert_resource(tuning_config.tuning);
 
     if !windowed {
         // Prevent auto-exit when there are zero windows by clearing AppExit (gated by exit flag)
@@ -167,13 +270,16 @@ fn main() {
         app.insert_resource(AllowExitFlag(exit_flag.clone()));
         {
             let f2 = exit_flag.clone();
+            let o2 = outcome.clone();
             let _ = ctrlc::set_handler(move || {
                 eprintln!("[diag] SIGINT received, requesting shutdown...");
+                o2.record(ExitReason::Interrupted);
                 f2.store(true, std::sync::atomic::Ordering::SeqCst);
             });
         }
         {
             let f3 = exit_flag; // move into thread without redundant clone
+            let o3 = outcome.clone();
             std::thread::spawn(move || {
                 use std::io::Read;
                 let mut stdin = std::io::stdin();
@@ -182,6 +288,7 @@ fn main() {
                     match stdin.read(&mut buf) {
                         Ok(0) => { // EOF
                             eprintln!("[diag] stdin EOF; requesting shutdown...");
+                            o3.record(ExitReason::StdinClosed);
                             f3.store(true, std::sync::atomic::Ordering::SeqCst);
                             break;
                         }
@@ -206,6 +313,15 @@ fn main() {
     if let Some(mut h) = ff_handle {
         let _ = h.child.wait();
     }
+    // Then say why the run ended, for batch scripts
+    if let Some(reason) = outcome.reason() {
+        let record = ExitRecord { reason, frame: sim_clock.frame(), sim_seconds: sim_clock.seconds() };
+        eprintln!("[diag] run ended: {:?} at frame {}", record.reason, record.frame);
+        let path = recording.with_extension("exit.json");
+        if let Err(e) = record.write(&path) {
+            eprintln!("[warn] exit reason {}: {e}", path.display());
+        }
+    }
 
 
 
@@ -223,3 +339,55 @@ fn prevent_exit(flag: Option<Res<AllowExitFlag>>, mut ev: ResMut<Events<AppExit>
 
 }
 
+
+// One `render` child per seed, in order, each into its own directory. Returns the exit code.
+fn run_sweep(cli: &Cli) -> i32 {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            eprintln!("[error] sweep: cannot find own executable: {e}");
+            return 2;
+        }
+    };
+    // Ctrl+C reaches the children too; they finish their MP4s and no further seeds start
+    let stop = std::sync::Arc::new(AtomicBool::new(false));
+    {
+        let stop = stop.clone();
+        let _ = ctrlc::set_handler(move || stop.store(true, Ordering::SeqCst));
+    }
+    let dir = cli.output_dir.join(format!("sweep-{}", chrono::Utc::now().format("%Y-%m-%d_%H-%M-%S")));
+    let mut failed = Vec::new();
+    for (i, &seed) in cli.seeds.iter().enumerate() {
+        if stop.load(Ordering::SeqCst) {
+            eprintln!("[diag] sweep interrupted; skipping {} seeds", cli.seeds.len() - i);
+            break;
+        }
+        let out = dir.join(format!("seed-{seed}"));
+        eprintln!("[diag] sweep {}/{}: seed {seed} -> {}", i + 1, cli.seeds.len(), out.display());
+        let mut child = std::process::Command::new(&exe);
+        child.args(cli.sweep_child_args(seed, &out)).stdin(std::process::Stdio::piped());
+        for var in SWEEP_CHILD_UNSET_ENV {
+            child.env_remove(var);
+        }
+        let spawned = child.spawn();
+        let status = spawned.and_then(|mut child| {
+            // Children stop on stdin EOF, so hold their stdin open until they exit
+            let _stdin = child.stdin.take();
+            child.wait()
+        });
+        match status {
+            Ok(s) if s.success() => {}
+            Ok(s) => {
+                eprintln!("[warn] sweep: seed {seed} exited with {s}");
+                failed.push(seed);
+            }
+            Err(e) => {
+                eprintln!("[warn] sweep: seed {seed} did not run: {e}");
+                failed.push(seed);
+            }
+        }
+    }
+    eprintln!("[diag] sweep done in {}; failed seeds: {failed:?}", dir.display());
+    if failed.is_empty() { 0 } else { 1 }
+}
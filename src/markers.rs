@@ -44,9 +44,10 @@ pub fn spawn_force_marker(
 }
 
 /// Shorter lifetime: full alpha for 0.5s, fade out by 2.0s, then despawn.
+/// Uses real (video) time so labels stay readable when the simulation is fast-forwarded.
 pub fn update_force_markers(
     mut commands: Commands,
-    time: Res<Time>,
+    time: Res<Time<Real>>,
     mut q: Query<(Entity, &mut ForceMarker, &mut TextColor)>,
 ) {
     let dt = time.delta_secs();
@@ -0,0 +1,152 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use bevy::prelude::Resource;
+
+use crate::tuning::AppState;
+
+/// Quantiles reported for each distribution.
+pub const QUANTILES: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Frame counts from the capture pipeline, bumped by the render bridge and the ffmpeg writer thread.
+#[derive(Clone, Default)]
+pub struct CaptureCounters {
+    captured: Arc<AtomicU64>,
+    dropped: Arc<AtomicU64>,
+    queued: Arc<AtomicU64>,
+    written: Arc<AtomicU64>,
+}
+
+impl CaptureCounters {
+    /// A frame read back from the GPU.
+    pub fn captured(&self, n: u64) {
+        self.captured.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// A captured frame that never reached ffmpeg (superseded in the same tick, the wrong size, or the writer gone).
+    pub fn dropped(&self, n: u64) {
+        self.dropped.fetch_add(n, Ordering::Relaxed);
+    }
+
+    /// A frame handed to the ffmpeg writer thread.
+    pub fn queued(&self) {
+        self.queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A queued frame written to ffmpeg's stdin (or discarded by the writer).
+    pub fn written(&self) {
+        self.written.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Frames waiting for the ffmpeg writer.
+    pub fn queue_depth(&self) -> u64 {
+        self.queued.load(Ordering::Relaxed).saturating_sub(self.written.load(Ordering::Relaxed))
+    }
+}
+
+/// Count, sum and quantiles of one distribution; quantiles are nearest-rank.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Summary {
+    pub count: u64,
+    pub sum: f64,
+    /// Paired with QUANTILES
+    pub quantiles: [f64; 3],
+}
+
+impl Summary {
+    pub fn of(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len();
+        let rank = |q: f64| values[((q * n as f64).ceil() as usize).clamp(1, n) - 1];
+        Self { count: n as u64, sum: values.iter().sum(), quantiles: QUANTILES.map(rank) }
+    }
+
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 { 0.0 } else { self.sum / self.count as f64 }
+    }
+}
+
+/// World state behind GET /metrics, published by the Bevy world.
+#[derive(Debug, Clone, Default)]
+pub struct WorldMetrics {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    pub population: u64,
+    pub joints: u64,
+    pub births: u64,
+    pub deaths: u64,
+    pub bites: u64,
+    /// Ages in life ticks
+    pub age: Summary,
+    pub life_points: Summary,
+    /// Wall-clock seconds per physics step, since startup
+    pub step_seconds_sum: f64,
+    pub step_count: u64,
+    pub last_step_seconds: f64,
+}
+
+/// Everything GET /metrics reports: the world mirror plus the live capture counters.
+#[derive(Resource, Clone, Default)]
+pub struct Metrics {
+    world: Arc<Mutex<WorldMetrics>>,
+    pub capture: CaptureCounters,
+}
+
+impl Metrics {
+    pub fn publish(&self, world: WorldMetrics) {
+        *self.world.lock().unwrap() = world;
+    }
+
+    /// Prometheus text exposition format, version 0.0.4.
+    pub fn render(&self) -> String {
+        let w = self.world.lock().unwrap().clone();
+        let c = &self.capture;
+        let mut out = String::new();
+        {
+            let mut metric = |name: &str, kind: &str, help: &str, value: f64| {
+                let _ = writeln!(out, "# HELP live_whirl_{name} {help}\n# TYPE live_whirl_{name} {kind}\nlive_whirl_{name} {value}");
+            };
+            metric("frame", "gauge", "Physics steps since startup.", w.frame as f64);
+            metric("sim_seconds", "gauge", "Simulated seconds since startup.", w.sim_seconds);
+            metric("population", "gauge", "Live balls.", w.population as f64);
+            metric("joints", "gauge", "Ball-to-ball joints.", w.joints as f64);
+            metric("births_total", "counter", "Balls born since startup.", w.births as f64);
+            metric("deaths_total", "counter", "Balls that died since startup.", w.deaths as f64);
+            metric("bites_total", "counter", "Bites since startup.", w.bites as f64);
+            metric("ball_age_ticks_mean", "gauge", "Mean ball age in life ticks.", w.age.mean());
+            metric("ball_life_points_mean", "gauge", "Mean ball life points.", w.life_points.mean());
+            metric("frames_captured_total", "counter", "Frames read back from the GPU.", c.captured.load(Ordering::Relaxed) as f64);
+            metric("frames_dropped_total", "counter", "Captured frames that did not reach ffmpeg.", c.dropped.load(Ordering::Relaxed) as f64);
+            metric("ffmpeg_queue_depth", "gauge", "Frames waiting to be written to ffmpeg.", c.queue_depth() as f64);
+            metric("physics_step_last_seconds", "gauge", "Wall-clock time of the latest physics step.", w.last_step_seconds);
+        }
+        // Quantiles are optional in a summary; physics step time only keeps sum and count
+        let mut summary = |name: &str, help: &str, s: &Summary, quantiles: bool| {
+            let _ = writeln!(out, "# HELP live_whirl_{name} {help}\n# TYPE live_whirl_{name} summary");
+            if quantiles && s.count > 0 {
+                for (q, v) in QUANTILES.iter().zip(s.quantiles) {
+                    let _ = writeln!(out, "live_whirl_{name}{{quantile=\"{q}\"}} {v}");
+                }
+            }
+            let _ = writeln!(out, "live_whirl_{name}_sum {}\nlive_whirl_{name}_count {}", s.sum, s.count);
+        };
+        summary("ball_age_ticks", "Ball age in life ticks.", &w.age, true);
+        summary("ball_life_points", "Ball life points.", &w.life_points, true);
+        let steps = Summary { count: w.step_count, sum: w.step_seconds_sum, quantiles: [0.0; 3] };
+        summary("physics_step_seconds", "Wall-clock time per physics step.", &steps, false);
+        out
+    }
+}
+
+async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], state.metrics().render())
+}
+
+pub fn metrics_routes() -> Router<AppState> {
+    Router::new().route("/metrics", get(get_metrics))
+}
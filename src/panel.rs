@@ -0,0 +1,15 @@
+use axum::{response::Html, routing::get, Router};
+
+use crate::tuning::AppState;
+
+// Self-contained (no external scripts or fonts) so it works on offline machines.
+// The page builds its controls from GET /tuning/schema and follows GET /live.
+const PANEL_HTML: &str = include_str!("panel.html");
+
+async fn panel() -> Html<&'static str> {
+    Html(PANEL_HTML)
+}
+
+pub fn panel_routes() -> Router<AppState> {
+    Router::new().route("/", get(panel))
+}
@@ -0,0 +1,236 @@
+use bevy::{
+    prelude::{
+        App, Assets, Changed, ChildOf, Children, Commands, Component, Entity, FixedUpdate,
+        IntoScheduleConfigs, NonSend, OnRemove, Plugin, Query, Res, ResMut, Resource, Time, Timer, TimerMode,
+        Transform, Trigger, Update, Vec2, With,
+    },
+    sprite::{ColorMaterial, MeshMaterial2d},
+};
+use bevy_rapier2d::prelude::{ImpulseJoint as BevyImpulseJoint, PhysicsSet, ReadRapierContext, RevoluteJointBuilder};
+
+use crate::{
+    arena::PegKind,
+    ball::{Ball, MAX_LIFE_POINTS},
+    pegs_api::{PegCommand, PegInfo, PegRx, PegsMirror},
+    setup::{peg_color, Peg, PegIndex},
+    tuning::PhysicsTuning,
+};
+
+#[derive(Resource)]
+struct PegTickTimer(pub Timer);
+
+// Temporary joint from a sticky peg to a ball; released when the timer finishes or broken by load in
+// crate::ball::unstick.
+#[derive(Component)]
+struct PegAnchor(Timer);
+
+// Ball recently released by a sticky peg; it is not re-anchored until the timer finishes.
+#[derive(Component)]
+struct PegCooldown(Timer);
+
+fn apply_peg_commands(
+    rx: Option<NonSend<PegRx>>,
+    mut q_pegs: Query<(&PegIndex, &mut PegKind, &MeshMaterial2d<ColorMaterial>), With<Peg>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(rx) = rx else { return; };
+    while let Ok(cmd) = rx.0.try_recv() {
+        match cmd {
+            PegCommand::SetKind { index, kind } => {
+                for (peg_index, mut peg_kind, material) in q_pegs.iter_mut() {
+                    if peg_index.0 != index {
+                        continue;
+                    }
+                    *peg_kind = kind;
+                    if let Some(m) = materials.get_mut(material) {
+                        m.color = peg_color(kind);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Republish the peg list for GET /pegs whenever a kind changes (including at spawn).
+fn publish_pegs(
+    mirror: Option<Res<PegsMirror>>,
+    q_changed: Query<(), (With<Peg>, Changed<PegKind>)>,
+    q_pegs: Query<(&PegIndex, &PegKind, &Transform), With<Peg>>,
+) {
+    let Some(mirror) = mirror else { return; };
+    if q_changed.is_empty() {
+        return;
+    }
+    let mut pegs: Vec<PegInfo> = q_pegs
+        .iter()
+        .map(|(index, kind, tf)| PegInfo { index: index.0, kind: *kind, x: tf.translation.x, y: tf.translation.y })
+        .collect();
+    pegs.sort_by_key(|p| p.index);
+    *mirror.0.lock().unwrap() = pegs;
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn peg_contacts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: ResMut<PegTickTimer>,
+    rapier: ReadRapierContext,
+    tuning: Res<PhysicsTuning>,
+    q_pegs: Query<(Entity, &PegKind, &Transform), With<Peg>>,
+    mut q_balls: Query<(&mut Ball, &Transform, Option<&Children>, Option<&PegCooldown>)>,
+    q_anchors: Query<(), With<PegAnchor>>,
+) {
+    let tick = timer.0.tick(time.delta()).just_finished();
+    let Ok(ctx) = rapier.single() else { return; };
+    // Balls anchored during this run; their children are not updated until commands apply
+    let mut anchored: Vec<Entity> = Vec::new();
+
+    for (peg_entity, kind, peg_tf) in q_pegs.iter() {
+        if *kind == PegKind::Inert {
+            continue;
+        }
+        for pair in ctx.contact_pairs_with(peg_entity) {
+            if !pair.has_any_active_contact() {
+                continue;
+            }
+            let other = if pair.collider1() == Some(peg_entity) { pair.collider2() } else { pair.collider1() };
+            let Some(ball_entity) = other else { continue; };
+            let Ok((mut ball, ball_tf, children, cooldown)) = q_balls.get_mut(ball_entity) else { continue; };
+            match kind {
+                PegKind::Inert => {}
+                PegKind::Nutrient if tick => {
+                    let fed = ball.life_points.saturating_add(tuning.peg_nutrient_per_tick).min(MAX_LIFE_POINTS);
+                    ball.life_points = fed.max(ball.life_points);
+                }
+                PegKind::Draining if tick => {
+                    ball.life_points = ball.life_points.saturating_sub(tuning.peg_drain_per_tick);
+                }
+                PegKind::Nutrient | PegKind::Draining => {}
+                PegKind::Sticky => {
+                    let already_anchored = anchored.contains(&ball_entity)
+                        || children.is_some_and(|c| c.iter().any(|child| q_anchors.contains(*child)));
+                    if already_anchored || cooldown.is_some() || tuning.peg_sticky_hold_secs <= 0.0 {
+                        continue;
+                    }
+                    // Pin the ball's center where it touched, relative to the peg
+                    let anchor: Vec2 = ball_tf.translation.truncate() - peg_tf.translation.truncate();
+                    let joint_entity = commands
+                        .spawn((
+                            BevyImpulseJoint::new(
+                                peg_entity,
+                                RevoluteJointBuilder::new().local_anchor1(anchor).local_anchor2(Vec2::ZERO).build(),
+                            ),
+                            PegAnchor(Timer::from_seconds(tuning.peg_sticky_hold_secs, TimerMode::Once)),
+                        ))
+                        .id();
+                    commands.entity(ball_entity).add_child(joint_entity);
+                    anchored.push(ball_entity);
+                }
+            }
+        }
+    }
+}
+
+fn release_peg_anchors(mut commands: Commands, time: Res<Time>, mut q_anchors: Query<(Entity, &mut PegAnchor)>) {
+    for (entity, mut anchor) in q_anchors.iter_mut() {
+        if anchor.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// However an anchor goes, give the ball a chance to fall away before the peg can grab it again.
+fn cool_down_released_ball(
+    trigger: Trigger<OnRemove, PegAnchor>,
+    mut commands: Commands,
+    tuning: Res<PhysicsTuning>,
+    q_parents: Query<&ChildOf>,
+) {
+    let Ok(child_of) = q_parents.get(trigger.target()) else { return; };
+    commands
+        .entity(child_of.parent())
+        .try_insert(PegCooldown(Timer::from_seconds(tuning.peg_sticky_hold_secs.max(0.0), TimerMode::Once)));
+}
+
+fn tick_peg_cooldowns(mut commands: Commands, time: Res<Time>, mut q_cooldowns: Query<(Entity, &mut PegCooldown)>) {
+    for (entity, mut cooldown) in q_cooldowns.iter_mut() {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<PegCooldown>();
+        }
+    }
+}
+
+pub struct PegPlugin;
+
+impl Plugin for PegPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PegTickTimer(Timer::from_seconds(0.5, TimerMode::Repeating)))
+            .add_observer(cool_down_released_ball)
+            .add_systems(Update, (apply_peg_commands, publish_pegs).chain())
+            .add_systems(
+                FixedUpdate,
+                (peg_contacts, release_peg_anchors, tick_peg_cooldowns).before(PhysicsSet::SyncBackend),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Events, Mesh};
+    use bevy_rapier2d::prelude::{Collider, ExternalForce, RigidBody, Velocity};
+
+    use super::*;
+    use crate::{
+        ball::{unstick, FrameCounter, JointLifetimeStats, BALL_RADIUS},
+        events::JointBroken,
+    };
+
+    // A ball torn off a sticky peg is not grabbed again while it still touches the peg.
+    #[test]
+    fn anchor_broken_by_load_is_not_regrabbed() {
+        let mut app = crate::setup::physics_test_app();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .init_resource::<FrameCounter>()
+            .init_resource::<JointLifetimeStats>()
+            .insert_resource(PhysicsTuning { break_force_threshold: 1.0e-3, peg_sticky_hold_secs: 100.0, ..Default::default() })
+            .insert_resource(PegTickTimer(Timer::from_seconds(0.5, TimerMode::Repeating)))
+            .add_event::<JointBroken>()
+            .add_observer(cool_down_released_ball)
+            .add_systems(Update, (peg_contacts, unstick).chain());
+        app.world_mut().spawn((Peg, PegKind::Sticky, RigidBody::Fixed, Collider::ball(BALL_RADIUS), Transform::from_xyz(0.0, 0.0, 0.0)));
+        // Resting on top of the peg, pushed sideways
+        let on_top = Transform::from_xyz(0.0, 2.0 * BALL_RADIUS - 0.5, 0.0);
+        let ball = app
+            .world_mut()
+            .spawn((
+                Ball::default(),
+                RigidBody::Dynamic,
+                Collider::ball(BALL_RADIUS),
+                Velocity::zero(),
+                ExternalForce { force: Vec2::new(1.0e3, 0.0), torque: 0.0 },
+                on_top,
+            ))
+            .id();
+        let anchors = |app: &mut App| app.world_mut().query::<&PegAnchor>().iter(app.world()).count();
+
+        let mut broken = 0;
+        for _ in 0..10 {
+            app.update();
+            broken += app.world_mut().resource_mut::<Events<JointBroken>>().drain().count();
+            if broken > 0 {
+                break;
+            }
+        }
+        assert_eq!(broken, 1, "the load broke the anchor");
+        assert_eq!(anchors(&mut app), 0);
+        assert!(app.world().get::<PegCooldown>(ball).is_some());
+
+        // Put it back on the peg, at rest: still touching, but not anchored again
+        app.world_mut().entity_mut(ball).insert((on_top, Velocity::zero(), ExternalForce::default()));
+        for _ in 0..10 {
+            app.update();
+            assert_eq!(anchors(&mut app), 0, "re-grabbed during the cooldown");
+        }
+    }
+}
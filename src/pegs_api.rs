@@ -0,0 +1,84 @@
+use std::sync::{mpsc, Arc, Mutex};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::PegKind;
+
+/// One peg as seen by API clients.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PegInfo {
+    pub index: u32,
+    pub kind: PegKind,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PegKindUpdate {
+    pub kind: PegKind,
+}
+
+/// Commands from the HTTP server to the Bevy world.
+#[derive(Debug, Clone, Copy)]
+pub enum PegCommand {
+    SetKind { index: u32, kind: PegKind },
+}
+
+// Not a Resource; see TuningRx.
+pub struct PegRx(pub mpsc::Receiver<PegCommand>);
+
+/// Peg list published by the Bevy world for GET /pegs.
+#[derive(Resource, Clone)]
+pub struct PegsMirror(pub Arc<Mutex<Vec<PegInfo>>>);
+
+/// Server-side handle to the peg channel and mirror.
+#[derive(Clone)]
+pub struct PegsApi {
+    tx: mpsc::Sender<PegCommand>,
+    mirror: Arc<Mutex<Vec<PegInfo>>>,
+}
+
+impl PegsApi {
+    /// Create the server handle plus the receiver and mirror the Bevy world needs.
+    pub fn channel() -> (Self, PegRx, PegsMirror) {
+        let (tx, rx) = mpsc::channel();
+        let mirror = Arc::new(Mutex::new(Vec::new()));
+        (Self { tx, mirror: mirror.clone() }, PegRx(rx), PegsMirror(mirror))
+    }
+}
+
+async fn get_pegs(State(api): State<PegsApi>) -> Json<Vec<PegInfo>> {
+    let guard = api.mirror.lock().unwrap();
+    Json(guard.clone())
+}
+
+async fn put_peg(
+    State(api): State<PegsApi>,
+    Path(index): Path<u32>,
+    Json(update): Json<PegKindUpdate>,
+) -> Result<Json<PegInfo>, StatusCode> {
+    let mut guard = api.mirror.lock().unwrap();
+    let Some(peg) = guard.iter_mut().find(|p| p.index == index) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    api.tx
+        .send(PegCommand::SetKind { index, kind: update.kind })
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)?;
+    // Reflect immediately; the Bevy world republishes the same value once applied
+    peg.kind = update.kind;
+    Ok(Json(*peg))
+}
+
+pub fn peg_routes(api: PegsApi) -> Router {
+    Router::new()
+        .route("/pegs", get(get_pegs))
+        .route("/pegs/{index}", put(put_peg))
+        .with_state(api)
+}
@@ -0,0 +1,182 @@
+use std::path::{Path, PathBuf};
+
+use axum::{
+    extract::{Path as UrlPath, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::tuning::{ApiTuning, AppState, PhysicsTuning, TuningValidationError, TuningViolation};
+
+/// Named `PhysicsTuning` snapshots, one `<name>.json` file each (same JSON shape as GET /tuning).
+#[derive(Debug, Clone)]
+pub struct PresetStore {
+    dir: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum PresetError {
+    /// Names are file stems: 1-64 of `[A-Za-z0-9_-]`
+    InvalidName(String),
+    NotFound(String),
+    /// The preset parsed but its values fail `PhysicsTuning::validate`
+    Invalid(Vec<TuningViolation>),
+    Io(String),
+    Parse(String),
+}
+
+impl std::fmt::Display for PresetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidName(name) => write!(f, "invalid preset name {name:?} (use 1-64 letters, digits, '-' or '_')"),
+            Self::NotFound(name) => write!(f, "no preset named {name:?}"),
+            Self::Invalid(violations) => write!(f, "preset has {} invalid value(s)", violations.len()),
+            Self::Io(e) => write!(f, "preset io: {e}"),
+            Self::Parse(e) => write!(f, "preset json: {e}"),
+        }
+    }
+}
+
+impl PresetStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// `$OUTPUT_DIR/presets`, defaulting to `./output/presets` next to the videos.
+    pub fn from_env() -> Self {
+        let out = std::env::var("OUTPUT_DIR").unwrap_or_else(|_| "./output".to_string());
+        Self::new(Path::new(&out).join("presets"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, name: &str) -> Result<PathBuf, PresetError> {
+        let valid = !name.is_empty()
+            && name.len() <= 64
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(PresetError::InvalidName(name.to_string()));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+
+    /// Preset names, sorted. A missing directory just means no presets yet.
+    pub fn list(&self) -> Result<Vec<String>, PresetError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(PresetError::Io(e.to_string())),
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+            .filter(|name| self.path_for(name).is_ok())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn load(&self, name: &str) -> Result<PhysicsTuning, PresetError> {
+        let path = self.path_for(name)?;
+        let text = std::fs::read_to_string(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => PresetError::NotFound(name.to_string()),
+            _ => PresetError::Io(e.to_string()),
+        })?;
+        let api: ApiTuning = serde_json::from_str(&text).map_err(|e| PresetError::Parse(e.to_string()))?;
+        let tuning = PhysicsTuning::from(api);
+        tuning.validate().map_err(PresetError::Invalid)?;
+        Ok(tuning)
+    }
+
+    pub fn save(&self, name: &str, tuning: &PhysicsTuning) -> Result<(), PresetError> {
+        let path = self.path_for(name)?;
+        std::fs::create_dir_all(&self.dir).map_err(|e| PresetError::Io(e.to_string()))?;
+        let text = serde_json::to_string_pretty(&ApiTuning::from(tuning)).map_err(|e| PresetError::Parse(e.to_string()))?;
+        std::fs::write(&path, text).map_err(|e| PresetError::Io(e.to_string()))
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), PresetError> {
+        let path = self.path_for(name)?;
+        std::fs::remove_file(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => PresetError::NotFound(name.to_string()),
+            _ => PresetError::Io(e.to_string()),
+        })
+    }
+}
+
+/// Error body for preset routes; `violations` is only present for presets that fail validation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetErrorBody {
+    pub error: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<TuningViolation>,
+}
+
+type PresetResult<T> = Result<T, (StatusCode, Json<PresetErrorBody>)>;
+
+impl PresetError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            PresetError::InvalidName(_) => StatusCode::BAD_REQUEST,
+            PresetError::NotFound(_) => StatusCode::NOT_FOUND,
+            PresetError::Invalid(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            PresetError::Io(_) | PresetError::Parse(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+fn preset_error(e: PresetError) -> (StatusCode, Json<PresetErrorBody>) {
+    let status = e.status();
+    let error = e.to_string();
+    let violations = match e {
+        PresetError::Invalid(violations) => violations,
+        _ => Vec::new(),
+    };
+    (status, Json(PresetErrorBody { error, violations }))
+}
+
+fn validation_error(e: TuningValidationError) -> (StatusCode, Json<PresetErrorBody>) {
+    (StatusCode::UNPROCESSABLE_ENTITY, Json(PresetErrorBody { error: e.error, violations: e.violations }))
+}
+
+async fn list_presets(State(state): State<AppState>) -> PresetResult<Json<Vec<String>>> {
+    state.presets().list().map(Json).map_err(preset_error)
+}
+
+async fn show_preset(State(state): State<AppState>, UrlPath(name): UrlPath<String>) -> PresetResult<Json<ApiTuning>> {
+    let tuning = state.presets().load(&name).map_err(preset_error)?;
+    Ok(Json(ApiTuning::from(&tuning)))
+}
+
+// Saves the live tuning, overwriting any preset of the same name.
+async fn save_preset(State(state): State<AppState>, UrlPath(name): UrlPath<String>) -> PresetResult<Json<ApiTuning>> {
+    let current = state.current_tuning();
+    state.presets().save(&name, &current).map_err(preset_error)?;
+    eprintln!("[diag] preset saved name={name}");
+    Ok(Json(ApiTuning::from(&current)))
+}
+
+async fn apply_preset(State(state): State<AppState>, UrlPath(name): UrlPath<String>) -> PresetResult<Json<ApiTuning>> {
+    let tuning = state.presets().load(&name).map_err(preset_error)?;
+    let applied = state.apply_tuning(&format!("preset {name}"), tuning).map_err(validation_error)?;
+    eprintln!("[diag] preset applied name={name}");
+    Ok(Json(ApiTuning::from(&applied)))
+}
+
+async fn delete_preset(State(state): State<AppState>, UrlPath(name): UrlPath<String>) -> PresetResult<StatusCode> {
+    state.presets().delete(&name).map_err(preset_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub fn preset_routes() -> Router<AppState> {
+    Router::new()
+        .route("/presets", get(list_presets))
+        .route("/presets/{name}", get(show_preset).put(save_preset).delete(delete_preset))
+        .route("/presets/{name}/apply", post(apply_preset))
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::pegs_api::PegInfo;
+use crate::balls::BallInfo;
+
+/// How long POST /query/region waits for the world before giving up.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Scent coordinates are bucketed on this grid when looking for the dominant scent.
+pub const SCENT_BUCKET: f32 = 0.25;
+
+/// Area to query, in arena pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case")]
+pub enum RegionShape {
+    Circle { x: f32, y: f32, radius: f32 },
+    Rect { x_min: f32, y_min: f32, x_max: f32, y_max: f32 },
+}
+
+impl RegionShape {
+    fn check(&self) -> Result<(), String> {
+        match *self {
+            Self::Circle { x, y, radius } => {
+                if !(x.is_finite() && y.is_finite() && radius.is_finite() && radius > 0.0) {
+                    return Err(format!("circle needs a finite centre and radius > 0, got radius {radius}"));
+                }
+            }
+            Self::Rect { x_min, y_min, x_max, y_max } => {
+                if ![x_min, y_min, x_max, y_max].iter().all(|v| v.is_finite()) || x_min >= x_max || y_min >= y_max {
+                    return Err("rect needs finite bounds with x_min < x_max and y_min < y_max".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Two balls held together by a joint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JointInfo {
+    pub a: u64,
+    pub b: u64,
+}
+
+/// Most common scent bucket among the balls in a region.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DominantScent {
+    /// Centre of the bucket
+    pub scent: [f32; 2],
+    pub count: usize,
+    /// Of the balls in the region
+    pub share: f64,
+}
+
+/// Aggregates over the balls in a region; means are absent when it is empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RegionStats {
+    pub ball_count: usize,
+    pub peg_count: usize,
+    pub joint_count: usize,
+    pub mean_life_points: Option<f64>,
+    pub mean_age: Option<f64>,
+    pub dominant_scent: Option<DominantScent>,
+}
+
+/// Bucket a scent onto the SCENT_BUCKET grid.
+pub fn scent_bucket(scent: [f32; 2]) -> [i32; 2] {
+    [(scent[0] / SCENT_BUCKET).round() as i32, (scent[1] / SCENT_BUCKET).round() as i32]
+}
+
+/// Per-bucket counts for [`dominant_scent_with`], kept by callers that judge every step.
+pub type ScentCounts = HashMap<[i32; 2], usize>;
+
+/// The most common scent bucket, ties going to the lower bucket so results are stable.
+pub fn dominant_scent<'a>(scents: impl IntoIterator<Item = &'a [f32; 2]>) -> Option<DominantScent> {
+    dominant_scent_with(&mut ScentCounts::new(), scents)
+}
+
+/// [`dominant_scent`], counting in `counts` (cleared first) so its allocation is reused.
+pub fn dominant_scent_with<'a>(counts: &mut ScentCounts, scents: impl IntoIterator<Item = &'a [f32; 2]>) -> Option<DominantScent> {
+    counts.clear();
+    let mut total = 0;
+    for scent in scents {
+        *counts.entry(scent_bucket(*scent)).or_default() += 1;
+        total += 1;
+    }
+    let (bucket, count) = counts.iter().map(|(b, c)| (*b, *c)).max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+    Some(DominantScent {
+        scent: [bucket[0] as f32 * SCENT_BUCKET, bucket[1] as f32 * SCENT_BUCKET],
+        count,
+        share: count as f64 / total as f64,
+    })
+}
+
+impl RegionStats {
+    pub fn of(balls: &[BallInfo], pegs: &[PegInfo], joints: &[JointInfo]) -> Self {
+        let n = balls.len();
+        let mean = |f: fn(&BallInfo) -> f64| (n > 0).then(|| balls.iter().map(f).sum::<f64>() / n as f64);
+        Self {
+            ball_count: n,
+            peg_count: pegs.len(),
+            joint_count: joints.len(),
+            mean_life_points: mean(|b| b.life_points as f64),
+            mean_age: mean(|b| b.age as f64),
+            dominant_scent: dominant_scent(balls.iter().map(|b| &b.genome.friendly_scent)),
+        }
+    }
+}
+
+/// Everything a region overlaps, as of the frame the world answered in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionResult {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    /// Sorted by id
+    pub balls: Vec<BallInfo>,
+    /// Sorted by index
+    pub pegs: Vec<PegInfo>,
+    /// Joints with at least one ball in the region
+    pub joints: Vec<JointInfo>,
+    pub stats: RegionStats,
+}
+
+/// A region query waiting for the world to answer.
+pub struct RegionRequest {
+    pub shape: RegionShape,
+    pub reply: oneshot::Sender<RegionResult>,
+}
+
+// Not a Resource; see TuningRx.
+pub struct QueryRx(pub mpsc::Receiver<RegionRequest>);
+
+/// Server-side handle for queries that need the physics world rather than a mirror.
+#[derive(Clone)]
+pub struct QueryApi {
+    tx: mpsc::Sender<RegionRequest>,
+}
+
+impl QueryApi {
+    /// Create the server handle plus the receiver the Bevy world answers from.
+    pub fn channel() -> (Self, QueryRx) {
+        let (tx, rx) = mpsc::channel();
+        (Self { tx }, QueryRx(rx))
+    }
+}
+
+async fn query_region(State(api): State<QueryApi>, Json(shape): Json<RegionShape>) -> Result<Json<RegionResult>, (StatusCode, String)> {
+    shape.check().map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    let unavailable = || (StatusCode::SERVICE_UNAVAILABLE, "simulation is not running".to_string());
+    let (reply, answer) = oneshot::channel();
+    api.tx.send(RegionRequest { shape, reply }).map_err(|_| unavailable())?;
+    match tokio::time::timeout(QUERY_TIMEOUT, answer).await {
+        Ok(Ok(result)) => Ok(Json(result)),
+        Ok(Err(_)) => Err(unavailable()),
+        Err(_) => Err((StatusCode::GATEWAY_TIMEOUT, "simulation did not answer in time".to_string())),
+    }
+}
+
+pub fn query_routes(api: QueryApi) -> Router {
+    Router::new().route("/query/region", post(query_region)).with_state(api)
+}
@@ -0,0 +1,122 @@
+use bevy::prelude::{App, ChildOf, Entity, NonSend, Plugin, Query, Res, Transform, Update, Vec2, With};
+use bevy_rapier2d::{
+    parry::shape::{Ball as BallShape, Cuboid, Shape},
+    prelude::{ImpulseJoint, QueryFilter, ReadRapierContext, Velocity},
+};
+
+use crate::{
+    arena::PegKind,
+    ball::Ball,
+    ball_systems::{ball_info, link_joint_partners, BallId},
+    pegs_api::PegInfo,
+    query::{QueryRx, RegionResult, RegionShape, RegionStats},
+    setup::{Peg, PegIndex},
+    sim::SimClock,
+};
+
+// Centre plus parry shape for a region, so it goes through the same intersect_shape path as spawning.
+fn region_shape(shape: RegionShape) -> (Vec2, Box<dyn Shape>) {
+    match shape {
+        RegionShape::Circle { x, y, radius } => (Vec2::new(x, y), Box::new(BallShape::new(radius))),
+        RegionShape::Rect { x_min, y_min, x_max, y_max } => (
+            Vec2::new(0.5 * (x_min + x_max), 0.5 * (y_min + y_max)),
+            Box::new(Cuboid::new([0.5 * (x_max - x_min), 0.5 * (y_max - y_min)].into())),
+        ),
+    }
+}
+
+// Runs in Update so queries are answered while paused too.
+#[allow(clippy::type_complexity)]
+fn answer_region_queries(
+    rx: Option<NonSend<QueryRx>>,
+    rapier: ReadRapierContext,
+    clock: Option<Res<SimClock>>,
+    q_balls: Query<(&BallId, &Ball, &Transform, Option<&Velocity>)>,
+    q_pegs: Query<(&PegIndex, &PegKind, &Transform), With<Peg>>,
+    q_ball_ids: Query<&BallId>,
+    q_joints: Query<(&ImpulseJoint, &ChildOf)>,
+) {
+    let Some(rx) = rx else { return; };
+    let Ok(ctx) = rapier.single() else { return; };
+    while let Ok(request) = rx.0.try_recv() {
+        let (center, shape) = region_shape(request.shape);
+        let mut hits: Vec<Entity> = Vec::new();
+        ctx.intersect_shape(center, 0.0, shape.as_ref(), QueryFilter::default(), |entity| {
+            hits.push(entity);
+            true
+        });
+
+        let mut balls = Vec::new();
+        let mut pegs = Vec::new();
+        for &entity in &hits {
+            if let Ok((id, ball, tf, velocity)) = q_balls.get(entity) {
+                balls.push(ball_info(*id, ball, tf, velocity));
+            } else if let Ok((index, kind, tf)) = q_pegs.get(entity) {
+                pegs.push(PegInfo { index: index.0, kind: *kind, x: tf.translation.x, y: tf.translation.y });
+            }
+        }
+        balls.sort_by_key(|b| b.id);
+        pegs.sort_by_key(|p| p.index);
+        // Joints with either end inside, partners filled in as for GET /balls
+        let joints = link_joint_partners(&mut balls, &q_joints, &q_ball_ids);
+
+        let stats = RegionStats::of(&balls, &pegs, &joints);
+        let (frame, sim_seconds) = clock.as_ref().map_or((0, 0.0), |c| (c.frame(), c.seconds()));
+        // The handler may have timed out; nothing to do then
+        let _ = request.reply.send(RegionResult { frame, sim_seconds, balls, pegs, joints, stats });
+    }
+}
+
+pub struct QuerySystemsPlugin;
+
+impl Plugin for QuerySystemsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, answer_region_queries);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ball::BALL_RADIUS, query::RegionRequest};
+    use bevy::prelude::{Entity, World};
+    use bevy_rapier2d::prelude::{Collider, RevoluteJointBuilder, RigidBody};
+    use std::sync::mpsc;
+
+    fn ball(world: &mut World, id: u64, x: f32) -> Entity {
+        let body = (RigidBody::Fixed, Collider::ball(BALL_RADIUS), Transform::from_xyz(x, 0.0, 0.0));
+        world.spawn((Ball::default(), BallId(id), body)).id()
+    }
+
+    #[test]
+    fn region_query_answers_with_what_rapier_overlaps() {
+        let mut app = crate::setup::physics_test_app();
+        let (tx, rx) = mpsc::channel();
+        app.insert_non_send_resource(QueryRx(rx)).add_systems(Update, answer_region_queries);
+        let world = app.world_mut();
+        let (inside, grazing, outside) = (ball(world, 1, 0.0), ball(world, 2, 100.0 + 0.5 * BALL_RADIUS), ball(world, 3, 400.0));
+        world.spawn((Peg, PegIndex(4), PegKind::Sticky, RigidBody::Fixed, Collider::ball(5.0), Transform::from_xyz(-50.0, 0.0, 0.0)));
+        for (parent, child) in [(inside, outside), (outside, grazing)] {
+            world.spawn((ImpulseJoint::new(parent, RevoluteJointBuilder::new().build()), ChildOf(child)));
+        }
+        app.update(); // Rapier picks up the new colliders
+
+        let (reply, mut answer) = tokio::sync::oneshot::channel();
+        tx.send(RegionRequest { shape: RegionShape::Circle { x: 0.0, y: 0.0, radius: 100.0 }, reply }).unwrap();
+        app.update();
+        let result = answer.try_recv().expect("answered within the frame");
+
+        let ids: Vec<(u64, Vec<u64>)> = result.balls.iter().map(|b| (b.id, b.joints.clone())).collect();
+        assert_eq!(ids, vec![(1, vec![3]), (2, vec![3])], "ball 2 only grazes the circle; ball 3 is outside");
+        assert_eq!(result.pegs.iter().map(|p| (p.index, p.kind)).collect::<Vec<_>>(), vec![(4, PegKind::Sticky)]);
+        assert_eq!(result.joints.iter().map(|j| (j.a, j.b)).collect::<Vec<_>>(), vec![(1, 3), (2, 3)]);
+        assert_eq!((result.stats.ball_count, result.stats.peg_count, result.stats.joint_count), (2, 1, 2));
+
+        let (reply, mut answer) = tokio::sync::oneshot::channel();
+        tx.send(RegionRequest { shape: RegionShape::Rect { x_min: 300.0, y_min: -10.0, x_max: 500.0, y_max: 10.0 }, reply }).unwrap();
+        app.update();
+        let result = answer.try_recv().unwrap();
+        assert_eq!(result.balls.iter().map(|b| b.id).collect::<Vec<_>>(), vec![3]);
+        assert!(result.pegs.is_empty());
+    }
+}
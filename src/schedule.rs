@@ -0,0 +1,310 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use bevy::prelude::{Res, ResMut};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::history::{diff_tuning, with_values, FieldChange};
+use crate::sim::SimClock;
+use crate::tuning::{AppState, ApiTuningUpdate, PhysicsTuning, TuningValidationError};
+
+/// When a scheduled change starts: `{"frame": 600}` or `{"seconds": 10.0}` of simulated time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAt {
+    Frame(u64),
+    Seconds(f64),
+}
+
+impl ScheduleAt {
+    fn reached(&self, frame: u64, seconds: f64) -> bool {
+        match *self {
+            Self::Frame(f) => frame >= f,
+            Self::Seconds(s) => seconds >= s,
+        }
+    }
+}
+
+/// Body of POST /tuning/schedule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRequest {
+    pub at: ScheduleAt,
+    /// Interpolate numeric fields linearly to their targets over this many simulated seconds;
+    /// booleans switch when the ramp ends. Omitted or 0 applies everything at once.
+    #[serde(default)]
+    pub ramp_secs: Option<f64>,
+    /// Targets, in the same shape as PATCH /tuning
+    pub update: ApiTuningUpdate,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A ramp re-sends the tuning at most this often, in simulated seconds; every send reaches Bevy
+/// and each /live client, so stepping it every physics frame would flood both.
+pub const RAMP_SEND_INTERVAL_SECS: f64 = 0.1;
+
+/// Progress of a ramp that has started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampProgress {
+    pub started_seconds: f64,
+    pub progress: f64,
+    // Per-field start/target values and when the tuning was last sent
+    #[serde(skip)]
+    fields: Vec<FieldChange>,
+    #[serde(skip)]
+    sent_seconds: f64,
+}
+
+impl RampProgress {
+    // One history entry for the ramp's own fields, from their start values to `current`, so undo
+    // returns them to where the ramp started without touching edits made meanwhile
+    fn log(&self, state: &AppState, current: &PhysicsTuning, source: &str, note: Option<String>) {
+        let start = with_values(current, self.fields.iter().map(|c| (c.field.clone(), c.before.clone())));
+        state.log_change(&start, current, source, note);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledChange {
+    pub id: u64,
+    pub at: ScheduleAt,
+    pub ramp_secs: Option<f64>,
+    pub update: ApiTuningUpdate,
+    pub note: Option<String>,
+    /// Present once a ramp is under way
+    pub ramp: Option<RampProgress>,
+}
+
+/// Pending and in-progress scheduled tuning changes. Finished ones leave the list and appear in the tuning history.
+#[derive(Debug, Default)]
+pub struct TuningSchedule {
+    changes: Vec<ScheduledChange>,
+    next_id: u64,
+}
+
+impl TuningSchedule {
+    pub fn changes(&self) -> &[ScheduledChange] {
+        &self.changes
+    }
+
+    pub fn add(&mut self, req: ScheduleRequest) -> ScheduledChange {
+        self.next_id += 1;
+        let change = ScheduledChange {
+            id: self.next_id,
+            at: req.at,
+            ramp_secs: req.ramp_secs.filter(|s| *s > 0.0),
+            update: req.update,
+            note: req.note,
+            ramp: None,
+        };
+        self.changes.push(change.clone());
+        change
+    }
+
+    /// Remove a change; a ramp in progress stops where it is. See `cancel_change` to also log it.
+    pub fn cancel(&mut self, id: u64) -> Option<ScheduledChange> {
+        let idx = self.changes.iter().position(|c| c.id == id)?;
+        Some(self.changes.remove(idx))
+    }
+}
+
+// Start values lerped toward targets; integers rounded, everything else switches at the end.
+fn ramp_values(fields: &[FieldChange], progress: f64) -> Vec<(String, Value)> {
+    fields
+        .iter()
+        .map(|c| {
+            let value = match (c.before.as_f64(), c.after.as_f64()) {
+                _ if progress >= 1.0 => c.after.clone(),
+                (Some(a), Some(b)) => {
+                    let v = a + (b - a) * progress;
+                    if c.after.is_u64() { Value::from(v.round().max(0.0) as u64) } else { Value::from(v) }
+                }
+                _ => c.before.clone(),
+            };
+            (c.field.clone(), value)
+        })
+        .collect()
+}
+
+/// Apply due changes and advance ramps. Runs each physics step, after the clock is published.
+pub fn run_tuning_schedule(state: Option<Res<AppState>>, clock: Res<SimClock>, mut tuning: ResMut<PhysicsTuning>) {
+    let Some(state) = state else { return; };
+    let (frame, seconds) = (clock.frame(), clock.seconds());
+    let mut schedule = state.schedule().lock().unwrap();
+    if schedule.changes.is_empty() {
+        return;
+    }
+
+    let mut finished = Vec::new();
+    for change in schedule.changes.iter_mut() {
+        if change.ramp.is_none() && !change.at.reached(frame, seconds) {
+            continue;
+        }
+        let source = format!("schedule {}", change.id);
+        let Some(ramp_secs) = change.ramp_secs else {
+            // One-shot
+            let update = change.update.clone();
+            match state.update_tuning(&source, change.note.clone(), |t| update.apply_to(t)) {
+                Ok(t) => *tuning = t,
+                Err(e) => eprintln!("[warn] {source} dropped: {} invalid value(s)", e.violations.len()),
+            }
+            finished.push(change.id);
+            continue;
+        };
+
+        let ramp = change.ramp.get_or_insert_with(|| {
+            let start = state.current_tuning();
+            let mut target = start.clone();
+            change.update.clone().apply_to(&mut target);
+            RampProgress { started_seconds: seconds, progress: 0.0, fields: diff_tuning(&start, &target), sent_seconds: seconds }
+        });
+        ramp.progress = ((seconds - ramp.started_seconds) / ramp_secs).clamp(0.0, 1.0);
+        if ramp.progress < 1.0 && seconds - ramp.sent_seconds < RAMP_SEND_INTERVAL_SECS {
+            continue;
+        }
+        ramp.sent_seconds = seconds;
+        let values = ramp_values(&ramp.fields, ramp.progress);
+        match state.update_tuning_unlogged(|t| *t = with_values(t, values)) {
+            Ok(t) => *tuning = t,
+            Err(e) => {
+                eprintln!("[warn] {source} stopped: {} invalid value(s)", e.violations.len());
+                finished.push(change.id);
+                continue;
+            }
+        }
+        if ramp.progress >= 1.0 {
+            ramp.log(&state, &tuning, &source, change.note.clone());
+            finished.push(change.id);
+        }
+    }
+    schedule.changes.retain(|c| !finished.contains(&c.id));
+}
+
+async fn list_schedule(State(state): State<AppState>) -> Json<Vec<ScheduledChange>> {
+    Json(state.schedule().lock().unwrap().changes().to_vec())
+}
+
+async fn add_schedule(
+    State(state): State<AppState>,
+    Json(req): Json<ScheduleRequest>,
+) -> Result<(StatusCode, Json<ScheduledChange>), (StatusCode, Json<TuningValidationError>)> {
+    // Reject targets that would be invalid if applied now; they are checked again when they fire
+    let mut target = state.current_tuning();
+    req.update.clone().apply_to(&mut target);
+    target.validate().map_err(|v| (StatusCode::UNPROCESSABLE_ENTITY, Json(TuningValidationError::new(v))))?;
+    let change = state.schedule().lock().unwrap().add(req);
+    eprintln!("[diag] tuning change scheduled id={} at={:?} ramp_secs={:?}", change.id, change.at, change.ramp_secs);
+    Ok((StatusCode::CREATED, Json(change)))
+}
+
+/// Cancel a scheduled change. A ramp already under way keeps the values it reached and is
+/// logged to the tuning history up to there, so it can be undone like a finished one.
+pub fn cancel_change(state: &AppState, id: u64) -> Option<ScheduledChange> {
+    let change = state.schedule().lock().unwrap().cancel(id)?;
+    if let Some(ramp) = &change.ramp {
+        ramp.log(state, &state.current_tuning(), &format!("schedule {id} cancelled"), change.note.clone());
+    }
+    Some(change)
+}
+
+async fn cancel_schedule(State(state): State<AppState>, Path(id): Path<u64>) -> Result<Json<ScheduledChange>, StatusCode> {
+    cancel_change(&state, id).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+pub fn schedule_routes() -> Router<AppState> {
+    Router::new()
+        .route("/tuning/schedule", get(list_schedule).post(add_schedule))
+        .route("/tuning/schedule/{id}", delete(cancel_schedule))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, Update};
+    use std::sync::{mpsc, Arc, Mutex};
+
+    // A world running just the schedule, stepped at 60 Hz; the receiver sees every tuning sent to Bevy
+    fn schedule_app() -> (App, AppState, SimClock, mpsc::Receiver<PhysicsTuning>) {
+        let (tx, rx) = mpsc::channel();
+        let clock = SimClock::default();
+        let state = AppState::new(tx, Arc::new(Mutex::new(PhysicsTuning::default()))).with_clock(clock.clone());
+        let mut app = App::new();
+        app.insert_resource(state.clone()).insert_resource(clock.clone()).insert_resource(PhysicsTuning::default());
+        app.add_systems(Update, run_tuning_schedule);
+        (app, state, clock, rx)
+    }
+
+    fn step_to(app: &mut App, clock: &SimClock, frames: std::ops::Range<u64>) {
+        for frame in frames {
+            clock.set(frame, frame as f64 / 60.0);
+            app.update();
+        }
+    }
+
+    fn ramp(ramp_secs: f64, update: serde_json::Value) -> ScheduleRequest {
+        ScheduleRequest { at: ScheduleAt::Frame(0), ramp_secs: Some(ramp_secs), update: serde_json::from_value(update).unwrap(), note: None }
+    }
+
+    fn logged_fields(state: &AppState) -> (String, Vec<String>) {
+        let history = state.history().lock().unwrap();
+        let last = history.entries().last().expect("a history entry");
+        (last.source.clone(), last.diff.iter().map(|c| c.field.clone()).collect())
+    }
+
+    #[test]
+    fn ramp_interpolates_and_rounds() {
+        let start = PhysicsTuning::default();
+        let target = PhysicsTuning { survival_cost_per_tick: 11, bite_size_scale: 3.0, bite_enabled: false, ..start.clone() };
+        let fields = diff_tuning(&start, &target);
+
+        let half = with_values(&start, ramp_values(&fields, 0.5));
+        assert_eq!(half.survival_cost_per_tick, 6);
+        assert_eq!(half.bite_size_scale, 2.0);
+        assert!(half.bite_enabled); // switches at the end
+
+        let done = with_values(&start, ramp_values(&fields, 1.0));
+        assert_eq!(done.survival_cost_per_tick, 11);
+        assert!(!done.bite_enabled);
+    }
+
+    #[test]
+    fn ramp_sends_sparingly_and_logs_only_its_own_fields() {
+        let (mut app, state, clock, rx) = schedule_app();
+        state.schedule().lock().unwrap().add(ramp(1.0, serde_json::json!({ "max_age": { "survival_cost_per_tick": 11 } })));
+        step_to(&mut app, &clock, 0..30);
+        // An unrelated edit while the ramp runs stays out of the ramp's history entry
+        state.update_tuning("patch", None, |t| t.bite_size_scale = 3.0).unwrap();
+        step_to(&mut app, &clock, 30..61);
+
+        let sends = rx.try_iter().count();
+        assert!(sends <= 12, "one send per {RAMP_SEND_INTERVAL_SECS}s plus the patch, got {sends}");
+        assert_eq!(state.current_tuning().survival_cost_per_tick, 11);
+        assert!(state.schedule().lock().unwrap().changes().is_empty());
+        assert_eq!(logged_fields(&state), ("schedule 1".to_string(), vec!["survival_cost_per_tick".to_string()]));
+
+        state.undo(None).unwrap();
+        let undone = state.current_tuning();
+        assert_eq!((undone.survival_cost_per_tick, undone.bite_size_scale), (1, 3.0));
+    }
+
+    #[test]
+    fn cancelled_ramp_is_logged_where_it_stopped() {
+        let (mut app, state, clock, _rx) = schedule_app();
+        state.schedule().lock().unwrap().add(ramp(1.0, serde_json::json!({ "max_age": { "survival_cost_per_tick": 11 } })));
+        step_to(&mut app, &clock, 0..31);
+        let reached = state.current_tuning().survival_cost_per_tick;
+        assert!(reached > 1 && reached < 11, "half way, got {reached}");
+
+        assert!(cancel_change(&state, 1).is_some_and(|c| c.ramp.is_some()));
+        step_to(&mut app, &clock, 31..90);
+        assert_eq!(state.current_tuning().survival_cost_per_tick, reached);
+        assert_eq!(logged_fields(&state), ("schedule 1 cancelled".to_string(), vec!["survival_cost_per_tick".to_string()]));
+        state.undo(None).unwrap();
+        assert_eq!(state.current_tuning().survival_cost_per_tick, 1);
+        assert!(cancel_change(&state, 1).is_none());
+    }
+}
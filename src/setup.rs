@@ -7,19 +7,34 @@ use bevy::{
         Color,
         Commands,
         Component,
+        DetectChanges,
+        Entity,
+        EventReader,
+        Fixed,
+        FixedUpdate,
         Handle,
         Image,
+        IntoScheduleConfigs,
+        Last,
         Mesh,
+        NonSend,
+        Or,
         Plugin,
+        PreUpdate,
         Query,
         Res,
         ResMut,
         Resource,
         Startup,
+        Time,
+        Timer,
+        TimerMode,
         Update,
         Transform,
         Vec2,
+        Virtual,
         With,
+        World,
     },
     render::{
         mesh::Mesh2d,
@@ -39,19 +54,35 @@ use bevy_rapier2d::{
     plugin::TimestepMode,
     prelude::{
         Collider,
+        ContactForceEvent,
+        ContactForceEventThreshold,
         NoUserData,
+        PhysicsSet,
         RapierConfiguration,
         RapierPhysicsPlugin,
+        ReadRapierContext,
+        RigidBody,
     },
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
 
-use crate::ball::BALL_RADIUS;
+use crate::arena::{ArenaConfig, BoundaryMode, PegKind, GROUND_POSITION, GROUND_WIDTH, WALL_HEIGHT};
+use crate::ball::{Ball, BALL_RADIUS};
+use crate::markers::ForceMarker;
 use crate::shared_consts::PIXELS_PER_METER;
+use crate::sim::{ResetSpec, SimClock, SimCommand, SimRx, SimStatus, SimStatusMirror, DEFAULT_SEED};
 
 #[derive(Resource)]
 pub struct RngResource {
     pub rng: StdRng,
+    /// What `rng` was last seeded with
+    pub seed: u64,
+}
+
+impl RngResource {
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: StdRng::seed_from_u64(seed), seed }
+    }
 }
 
 #[derive(Resource, Default)]
@@ -72,7 +103,6 @@ pub fn setup_meshes(mut meshes: ResMut<Assets<Mesh>>, mut commands: Commands) {
 pub struct VideoExportRequest {
     pub width: u32,
     pub height: u32,
-    pub fps: u32,
 }
 
 // Simplified offscreen render target setup based on Bevy's headless example
@@ -105,10 +135,179 @@ fn setup_render_target(
     )
 }
 
+/// Simulation clock, independent of the video frame rate.
+/// `physics_hz` sets both the Rapier step and the fixed-step schedule the ball systems run in,
+/// `substeps` splits each Rapier step, and `speed` scales simulated time against the
+/// video clock (10.0 = fast-forward, 0.25 = slow motion).
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SimTiming {
+    pub physics_hz: f64,
+    pub substeps: usize,
+    pub speed: f32,
+}
+
+impl SimTiming {
+    /// Read `PHYSICS_HZ`, `PHYSICS_SUBSTEPS` and `SIM_SPEED`; physics defaults to one step per video frame.
+    pub fn from_env(default_hz: u32) -> Self {
+        Self::from_env_with(default_hz, |key| std::env::var(key).ok())
+    }
+
+    /// Unparseable or out-of-range values fall back to the defaults.
+    pub fn from_env_with(default_hz: u32, env: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: std::str::FromStr>(raw: Option<String>) -> Option<T> {
+            raw.and_then(|s| s.trim().parse().ok())
+        }
+        Self {
+            physics_hz: parse::<f64>(env("PHYSICS_HZ")).filter(|hz| hz.is_finite() && *hz > 0.0).unwrap_or(default_hz as f64),
+            substeps: parse::<usize>(env("PHYSICS_SUBSTEPS")).filter(|n| *n > 0).unwrap_or(1),
+            speed: parse::<f32>(env("SIM_SPEED")).filter(|s| s.is_finite() && *s >= 0.0).unwrap_or(1.0),
+        }
+    }
+
+    pub fn dt(&self) -> f32 {
+        (1.0 / self.physics_hz) as f32
+    }
+}
+
+// Push SimTiming into Rapier, the fixed-step clock and the virtual clock whenever it changes.
+fn apply_sim_timing(
+    timing: Res<SimTiming>,
+    mut timestep_mode: ResMut<TimestepMode>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    if !timing.is_changed() {
+        return;
+    }
+    *timestep_mode = TimestepMode::Fixed {
+        dt: timing.dt(),
+        substeps: timing.substeps.max(1),
+    };
+    fixed_time.set_timestep_seconds(1.0 / timing.physics_hz);
+    virtual_time.set_relative_speed(timing.speed.max(0.0));
+    eprintln!(
+        "[diag] sim timing: physics {:.1} Hz x{} substeps, speed {:.2}x",
+        timing.physics_hz, timing.substeps, timing.speed,
+    );
+}
+
+/// Physics steps requested with POST /sim/step that have not run yet.
+#[derive(Resource, Debug, Default)]
+struct PendingSteps(u32);
+
+// Most steps run in one frame, so a large /sim/step?n= cannot stall rendering
+const MAX_STEPS_PER_FRAME: u32 = 240;
+
+/// Reset requested with POST /sim/reset, applied by `reset_world` at the start of the next frame.
+#[derive(Resource, Debug, Default)]
+pub(crate) struct SimReset {
+    pending: Option<ResetSpec>,
+    /// Resets applied so far; crate::stop watches it to start each rebuilt world afresh
+    pub(crate) count: u32,
+}
+
+// Pausing the virtual clock stops the fixed-step loop, which freezes Rapier and every ball timer
+// together while rendering (and video capture) carries on with a still frame.
+fn apply_sim_commands(
+    rx: Option<NonSend<SimRx>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut timing: ResMut<SimTiming>,
+    mut pending: ResMut<PendingSteps>,
+    mut reset: ResMut<SimReset>,
+) {
+    let Some(rx) = rx else { return; };
+    while let Ok(cmd) = rx.0.try_recv() {
+        eprintln!("[diag] sim control: {cmd:?}");
+        match cmd {
+            SimCommand::Pause => virtual_time.pause(),
+            SimCommand::Resume => {
+                pending.0 = 0;
+                virtual_time.unpause();
+            }
+            SimCommand::Step(n) if virtual_time.is_paused() => pending.0 = pending.0.saturating_add(n),
+            SimCommand::Step(_) => {}
+            SimCommand::Speed(speed) => timing.speed = speed,
+            SimCommand::Reset(spec) => {
+                pending.0 = 0;
+                reset.pending = Some(spec);
+            }
+        }
+    }
+}
+
+// Tear down everything the world spawned (balls with their joints, markers, walls and pegs) and
+// rebuild the arena from a fresh seed. Cameras, the render target and the capture pipeline are left
+// alone, so ffmpeg and the preview keep streaming; frame and sim time keep counting.
+fn reset_world(world: &mut World) {
+    let Some(spec) = world.resource_mut::<SimReset>().pending.take() else { return; };
+    world.resource_mut::<SimReset>().count += 1;
+    let doomed: Vec<Entity> = world
+        .query_filtered::<Entity, Or<(With<Ball>, With<ForceMarker>, With<Wall>, With<Peg>)>>()
+        .iter(world)
+        .collect();
+    for entity in doomed {
+        // Children go with their parent, so some may already be gone
+        if let Ok(e) = world.get_entity_mut(entity) {
+            e.despawn();
+        }
+    }
+    if let Some(arena) = spec.arena {
+        world.insert_resource(arena);
+    }
+    world.insert_resource(RngResource::seeded(spec.seed));
+    if let Err(e) = world.run_system_cached(setup_whirl) {
+        eprintln!("[error] reset: rebuilding the arena failed: {e}");
+    }
+    eprintln!("[diag] world reset seed={}", spec.seed);
+}
+
+// Run requested steps through FixedMain the way Bevy's fixed loop does. That loop
+// (bevy_time's `run_fixed_main_schedule`) only steps time accumulated from the virtual clock,
+// which is paused here, and cannot be fed steps from outside, so its invariant is repeated:
+// each run advances `Time<Fixed>` by one timestep and exposes it as the generic `Time`, and
+// afterwards `Time` is the virtual clock again. Recheck against bevy_time when upgrading Bevy.
+fn run_pending_steps(world: &mut World) {
+    let n = world.resource::<PendingSteps>().0.min(MAX_STEPS_PER_FRAME);
+    if n == 0 {
+        return;
+    }
+    world.resource_mut::<PendingSteps>().0 -= n;
+    let timestep = world.resource::<Time<Fixed>>().timestep();
+    for _ in 0..n {
+        world.resource_mut::<Time<Fixed>>().advance_by(timestep);
+        let fixed = world.resource::<Time<Fixed>>().as_generic();
+        *world.resource_mut::<Time>() = fixed;
+        world.run_schedule(bevy::app::FixedMain);
+    }
+    let virt = world.resource::<Time<Virtual>>().as_generic();
+    *world.resource_mut::<Time>() = virt;
+}
+
+fn publish_sim_status(
+    mirror: Option<Res<SimStatusMirror>>,
+    clock: Option<Res<SimClock>>,
+    virtual_time: Res<Time<Virtual>>,
+    timing: Res<SimTiming>,
+    pending: Res<PendingSteps>,
+    reset: Res<SimReset>,
+    rng: Res<RngResource>,
+) {
+    let Some(mirror) = mirror else { return; };
+    let (frame, sim_seconds) = clock.map_or((0, 0.0), |c| (c.frame(), c.seconds()));
+    *mirror.0.lock().unwrap() = SimStatus {
+        paused: virtual_time.is_paused(),
+        speed: timing.speed,
+        pending_steps: pending.0,
+        frame,
+        sim_seconds,
+        seed: rng.seed,
+        resets: reset.count,
+    };
+}
+
 pub fn setup_graphics(
     mut commands: Commands,
     mut rapier_config_q: Query<&mut RapierConfiguration, With<bevy_rapier2d::plugin::context::DefaultRapierContext>>,
-    mut timestep_mode: ResMut<TimestepMode>,
     mut images: ResMut<Assets<Image>>,
     video_req: Option<Res<VideoExportRequest>>,
 ) {
@@ -116,7 +315,6 @@ pub fn setup_graphics(
     let export = video_req.as_deref().copied().unwrap_or(VideoExportRequest {
         width: 1080,
         height: 1920,
-        fps: std::env::var("VIDEO_FPS").ok().and_then(|s| s.parse().ok()).unwrap_or(60),
     });
 
     // Create a simple offscreen render target only when video export is requested
@@ -132,10 +330,6 @@ pub fn setup_graphics(
     if let Ok(mut rc) = rapier_config_q.single_mut() {
         rc.gravity = Vec2::new(0.0, -9.8 * PIXELS_PER_METER * 0.000_625 * 100.0);
     }
-    *timestep_mode = TimestepMode::Fixed {
-        dt: 1.0 / export.fps as f32,
-        substeps: 1,
-    };
 
     let scale_x = GROUND_WIDTH / export.width as f32;
     let scale_y = WALL_HEIGHT / export.height as f32;
@@ -165,50 +359,118 @@ pub fn setup_graphics(
 
 }
 
+/// Contact-force event threshold derived from tuning; applied per collider as
+/// `ContactForceEventThreshold` on every ball so Rapier drops negligible contacts.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct ContactForceThreshold(pub f32);
+
+impl Default for ContactForceThreshold {
+    fn default() -> Self {
+        // Until the first update, let every contact through
+        Self(0.0)
+    }
+}
+
+/// Contact-force event counters. `filtered` counts active ball contacts that produced no event.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct ContactForceEventStats {
+    pub delivered: u64,
+    pub filtered: u64,
+}
+
+#[derive(Resource)]
+struct ContactForceStatsLogTimer(pub Timer);
+
 // Map rel_vel_min to a Rapier contact-force event threshold using a tunable fudge factor.
 fn update_contact_force_threshold(
-    mut rapier_cfg: bevy::prelude::Query<
-        &mut bevy_rapier2d::prelude::RapierConfiguration,
-        bevy::prelude::With<bevy_rapier2d::plugin::context::DefaultRapierContext>
-    >,
-    timestep: bevy::prelude::Res<bevy_rapier2d::plugin::TimestepMode>,
-    tuning: bevy::prelude::Res<crate::tuning::PhysicsTuning>,
+    timestep: Res<TimestepMode>,
+    tuning: Res<crate::tuning::PhysicsTuning>,
+    mut threshold: ResMut<ContactForceThreshold>,
 ) {
-    let Ok(mut rc) = rapier_cfg.single_mut() else { return; };
-
     // Collider density used at spawn
     let density = 0.001_f32;
 
-    // Radius in meters
-    let ppm = crate::shared_consts::PIXELS_PER_METER;
-    let r_m = crate::ball::BALL_RADIUS / ppm;
-    let area_m2 = std::f32::consts::PI * r_m * r_m;
+    // Colliders are sized in pixels and Rapier applies the density to that area,
+    // so mass (and hence force) is in pixel units, like ContactForceEvent magnitudes.
+    let r = crate::ball::BALL_RADIUS;
+    let area = std::f32::consts::PI * r * r;
 
     // Approx mass and effective mass (two equal bodies)
-    let m = density * area_m2;
+    let m = density * area;
     let m_eff = 0.5 * m;
 
     // Fixed timestep
     let dt = match *timestep {
-        bevy_rapier2d::plugin::TimestepMode::Fixed { dt, .. } => dt,
+        TimestepMode::Fixed { dt, .. } => dt,
         _ => 1.0 / 60.0,
     };
 
     // Effective multiplier: (1 - fudge), clamped
     let k_eff = (1.0 - tuning.contact_force_performance_cutoff_fudge_factor).clamp(0.0, 1.0);
-    let _threshold_force = k_eff * (m_eff / dt) * tuning.rel_vel_min;
+    let threshold_force = (k_eff * (m_eff / dt) * tuning.rel_vel_min).max(0.0);
 
-    // If supported by current bevy_rapier2d version, set the global contact force threshold.
-    // Note: Some versions may not expose this field. If it's unavailable, this will be a no-op at compile-time.
-    let _ = &mut rc;
-    // rc.contact_force_event_threshold = _threshold_force.max(0.0);
+    // Only touch the resource on change so the refresh below runs on tuning/fps changes
+    if threshold.0 != threshold_force {
+        threshold.0 = threshold_force;
+    }
 }
 
-pub const WALL_HEIGHT: f32 = 9.0 * PIXELS_PER_METER * 1.620_689_6;
-pub const GROUND_WIDTH: f32 = 8.0 * PIXELS_PER_METER;
-pub const WALL_THICKNESS: f32 = 0.1 * PIXELS_PER_METER;
+// Push a changed threshold onto every live ball collider.
+fn refresh_contact_force_thresholds(
+    threshold: Res<ContactForceThreshold>,
+    mut q_thresholds: Query<&mut ContactForceEventThreshold, With<crate::ball::Ball>>,
+) {
+    if !threshold.is_changed() {
+        return;
+    }
+    for mut t in q_thresholds.iter_mut() {
+        t.0 = threshold.0;
+    }
+}
+
+// Compare active ball contacts against delivered events to measure what the threshold saves.
+fn count_contact_force_events(
+    rapier: ReadRapierContext,
+    mut events: EventReader<ContactForceEvent>,
+    q_is_ball: Query<(), With<crate::ball::Ball>>,
+    mut stats: ResMut<ContactForceEventStats>,
+) {
+    let delivered = events
+        .read()
+        .filter(|e| q_is_ball.contains(e.collider1) || q_is_ball.contains(e.collider2))
+        .count() as u64;
+    let Ok(ctx) = rapier.single() else { return; };
+    let active = ctx
+        .simulation
+        .narrow_phase
+        .contact_pairs()
+        .filter(|pair| pair.has_any_active_contact)
+        .filter(|pair| {
+            [pair.collider1, pair.collider2].iter().any(|h| {
+                ctx.colliders.collider_entity(*h).is_some_and(|e| q_is_ball.contains(e))
+            })
+        })
+        .count() as u64;
+    stats.delivered += delivered;
+    stats.filtered += active.saturating_sub(delivered);
+}
+
+fn log_contact_force_event_stats(
+    time: Res<Time>,
+    mut timer: ResMut<ContactForceStatsLogTimer>,
+    threshold: Res<ContactForceThreshold>,
+    stats: Res<ContactForceEventStats>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    eprintln!(
+        "[diag] contact_force_events threshold={:.3} delivered={} filtered={}",
+        threshold.0, stats.delivered, stats.filtered,
+    );
+}
 
-const GROUND_POSITION: f32 = -0.5 * WALL_HEIGHT;
+pub const WALL_THICKNESS: f32 = 0.1 * PIXELS_PER_METER;
 
 struct Box2D {
     min_x: f32,
@@ -245,11 +507,40 @@ impl Default for Peg {
     }
 }
 
+/// Position of a peg in spawn order; the key for per-peg kind assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct PegIndex(pub u32);
+
+pub fn peg_color(kind: PegKind) -> Color {
+    match kind {
+        PegKind::Inert => Color::hsl(0.0, 0.0, 1.0),
+        PegKind::Nutrient => Color::hsl(120.0, 0.8, 0.6),
+        PegKind::Draining => Color::hsl(0.0, 0.8, 0.6),
+        PegKind::Sticky => Color::hsl(45.0, 0.9, 0.6),
+    }
+}
+
+// Floor openings as (min_x, max_x) spans; empty unless in recycle mode.
+fn drain_spans(arena: &ArenaConfig) -> Vec<(f32, f32)> {
+    if arena.boundary != BoundaryMode::Recycle || arena.drain_count == 0 {
+        return Vec::new();
+    }
+    let n = arena.drain_count as f32;
+    let half = (0.5 * arena.drain_width * 2.0 * BALL_RADIUS).min(0.5 * GROUND_WIDTH / n);
+    (0..arena.drain_count)
+        .map(|k| {
+            let center = WALL_BOX.min_x + (k as f32 + 0.5) * GROUND_WIDTH / n;
+            (center - half, center + half)
+        })
+        .collect()
+}
+
 pub fn setup_whirl(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut rng_resource: ResMut<RngResource>,
+    arena: Res<ArenaConfig>,
 ) {
     let rng = &mut rng_resource.rng;
     let mut add_wall = |size: Vec2, position: Vec2| {
@@ -261,17 +552,33 @@ pub fn setup_whirl(
             Transform::from_xyz(position.x, position.y, 0.0),
         ));
     };
-    println!("Setting up whirl");
+    println!("Setting up whirl ({:?} boundary)", arena.boundary);
     const VERTICAL_WALLS_SIZE: Vec2 = Vec2::new(WALL_BOX.max_x - WALL_BOX.min_x, WALL_THICKNESS);
     const HORIZONTAL_WALLS_SIZE: Vec2 = Vec2::new(WALL_THICKNESS, WALL_BOX.max_y - WALL_BOX.min_y);
-    add_wall(
-        VERTICAL_WALLS_SIZE,
-        Vec2 {
-            x: 0.0,
-            y: WALL_BOX.min_y,
-        },
-    );
-    add_wall(VERTICAL_WALLS_SIZE, Vec2::new(0.0, WALL_BOX.max_y));
+    match arena.boundary {
+        BoundaryMode::Closed => {
+            add_wall(
+                VERTICAL_WALLS_SIZE,
+                Vec2 {
+                    x: 0.0,
+                    y: WALL_BOX.min_y,
+                },
+            );
+            add_wall(VERTICAL_WALLS_SIZE, Vec2::new(0.0, WALL_BOX.max_y));
+        }
+        BoundaryMode::Wrap => {}
+        BoundaryMode::Recycle => {
+            // Floor segments between the drain openings
+            let mut x0 = WALL_BOX.min_x;
+            for (gap_min, gap_max) in drain_spans(&arena).into_iter().chain(std::iter::once((WALL_BOX.max_x, WALL_BOX.max_x))) {
+                if gap_min > x0 {
+                    add_wall(Vec2::new(gap_min - x0, WALL_THICKNESS), Vec2::new(0.5 * (x0 + gap_min), WALL_BOX.min_y));
+                }
+                x0 = gap_max;
+            }
+            add_wall(VERTICAL_WALLS_SIZE, Vec2::new(0.0, WALL_BOX.max_y));
+        }
+    }
     add_wall(HORIZONTAL_WALLS_SIZE, Vec2::new(WALL_BOX.min_x, 0.0));
     add_wall(HORIZONTAL_WALLS_SIZE, Vec2::new(WALL_BOX.max_x, 0.0));
 
@@ -280,6 +587,23 @@ pub fn setup_whirl(
     const SPACED_WIDTH: i32 = (GROUND_WIDTH / HORIZONTAL_SPACING) as i32;
     const SPACED_HEIGHT: i32 = (WALL_HEIGHT / VERTICAL_SPACING) as i32;
 
+    // Pegs are fixed bodies so sticky pegs can hold joints; see crate::pegs
+    let mut next_peg_index: u32 = 0;
+    let mut spawn_peg = |commands: &mut Commands, rng: &mut StdRng, size: Vec2, position: Vec2| {
+        let index = next_peg_index;
+        next_peg_index += 1;
+        let kind = arena.peg_kind_for(index, || rng.gen_range(0.0, 1.0));
+        commands.spawn((
+            Peg,
+            PegIndex(index),
+            kind,
+            RigidBody::Fixed,
+            Collider::cuboid(size.x / 2.0, size.y / 2.0),
+            Mesh2d(meshes.add(bevy::math::primitives::Rectangle::from_size(size))),
+            MeshMaterial2d(materials.add(ColorMaterial::from(peg_color(kind)))),
+            Transform::from_xyz(position.x, position.y, 0.0),
+        ));
+    };
     for i in 0..SPACED_WIDTH {
         let x = (i as f32 * HORIZONTAL_SPACING) - (0.5 * GROUND_WIDTH);
         for j in 1..SPACED_HEIGHT {
@@ -294,14 +618,7 @@ pub fn setup_whirl(
             }
             let size = Vec2::new(BALL_RADIUS, BALL_RADIUS);
             let position = Vec2::new(x + row_shift, y);
-            commands.spawn((
-                Peg,
-                Collider::cuboid(size.x / 2.0, size.y / 2.0),
-                Mesh2d(meshes.add(bevy::math::primitives::Rectangle::from_size(size))),
-                MeshMaterial2d(materials.add(ColorMaterial::from(Color::hsl(0.0, 0.0, 1.0)))),
-                // Raise pegs to z=2 so they are above the walls (z=0) like balls
-                Transform::from_xyz(position.x, position.y, 0.0),
-            ));
+            spawn_peg(&mut commands, rng, size, position);
             let is_pocket = (i != SPACED_WIDTH) && rng.gen_range(0.0, 1.0) < 0.025;
             if is_pocket {
                 let size = Vec2::new(BALL_RADIUS * 6.0, BALL_RADIUS);
@@ -309,13 +626,7 @@ pub fn setup_whirl(
                     x + row_shift + (HORIZONTAL_SPACING / 2.0),
                     y - (VERTICAL_SPACING / 3.0),
                 );
-                commands.spawn((
-                    Peg,
-                    Collider::cuboid(size.x / 2.0, size.y / 2.0),
-                    Mesh2d(meshes.add(bevy::math::primitives::Rectangle::from_size(size))),
-                    MeshMaterial2d(materials.add(ColorMaterial::from(Color::hsl(0.0, 0.0, 1.0)))),
-                    Transform::from_xyz(position.x, position.y, 0.0),
-                ));
+                spawn_peg(&mut commands, rng, size, position);
             }
         }
     }
@@ -325,16 +636,227 @@ pub struct SetupPlugin;
 
 impl Plugin for SetupPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(RngResource {
-            rng: StdRng::seed_from_u64(42),
-        });
+        if !app.world().contains_resource::<RngResource>() {
+            app.insert_resource(RngResource::seeded(DEFAULT_SEED));
+        }
+        if !app.world().contains_resource::<ArenaConfig>() {
+            app.insert_resource(ArenaConfig::from_env());
+        }
         app.add_plugins((
-            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER),
+            // Step physics in FixedUpdate alongside the ball systems (see SimTiming)
+            RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER).in_fixed_schedule(),
         ));
+        if !app.world().contains_resource::<SimTiming>() {
+            app.insert_resource(SimTiming::from_env(60));
+        }
+        app.init_resource::<PendingSteps>();
+        app.init_resource::<SimReset>();
+        app.add_systems(PreUpdate, (apply_sim_commands, reset_world, apply_sim_timing, run_pending_steps).chain());
+        app.add_systems(Last, publish_sim_status);
         app.add_systems(Startup, setup_meshes);
         app.add_systems(Startup, setup_graphics);
         app.add_systems(Startup, setup_whirl);
-        // Keep the global contact-force threshold in sync with tuning and fps
-        app.add_systems(Update, update_contact_force_threshold);
+        // Keep the per-collider contact-force threshold in sync with tuning and fps
+        app.insert_resource(ContactForceThreshold::default());
+        app.insert_resource(ContactForceEventStats::default());
+        app.insert_resource(ContactForceStatsLogTimer(Timer::from_seconds(10.0, TimerMode::Repeating)));
+        app.add_systems(Update, (update_contact_force_threshold, refresh_contact_force_thresholds).chain());
+        // Count per physics step so delivered events line up with the narrow phase
+        app.add_systems(FixedUpdate, count_contact_force_events.before(PhysicsSet::SyncBackend));
+        app.add_systems(Update, log_contact_force_event_stats);
+    }
+}
+
+/// Headless app with Rapier stepping once per `update()` at 60 Hz, for tests of systems that need
+/// a physics world. Rendering, the arena and the ball plugins are left out.
+#[cfg(test)]
+pub(crate) fn physics_test_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        bevy::MinimalPlugins,
+        bevy::transform::TransformPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(PIXELS_PER_METER),
+    ));
+    app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(std::time::Duration::from_secs_f64(1.0 / 60.0)));
+    app
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier2d::prelude::{ActiveEvents, ExternalForce};
+
+    use std::collections::BTreeMap;
+
+    use crate::tuning::PhysicsTuning;
+
+    #[test]
+    fn contact_force_threshold_follows_tuning_and_timestep() {
+        let mut app = App::new();
+        app.insert_resource(TimestepMode::Fixed { dt: 1.0 / 60.0, substeps: 1 })
+            .insert_resource(PhysicsTuning { rel_vel_min: 2.0, contact_force_performance_cutoff_fudge_factor: 0.5, ..Default::default() })
+            .init_resource::<ContactForceThreshold>()
+            .add_systems(Update, (update_contact_force_threshold, refresh_contact_force_thresholds).chain());
+        let ball = app.world_mut().spawn((Ball::default(), ContactForceEventThreshold(0.0))).id();
+        let threshold = |app: &App| app.world().resource::<ContactForceThreshold>().0;
+
+        app.update();
+        let base = threshold(&app);
+        assert!(base > 0.0);
+        assert_eq!(app.world().get::<ContactForceEventThreshold>(ball).unwrap().0, base, "pushed onto live balls");
+
+        // Proportional to rel_vel_min and to the step rate; a fudge factor of 1 lets every contact through
+        app.world_mut().resource_mut::<PhysicsTuning>().rel_vel_min = 4.0;
+        app.update();
+        assert!((threshold(&app) / base - 2.0).abs() < 1e-4);
+        app.insert_resource(TimestepMode::Fixed { dt: 1.0 / 120.0, substeps: 1 });
+        app.update();
+        assert!((threshold(&app) / base - 4.0).abs() < 1e-4);
+        app.world_mut().resource_mut::<PhysicsTuning>().contact_force_performance_cutoff_fudge_factor = 1.0;
+        app.update();
+        assert_eq!(threshold(&app), 0.0);
+        assert_eq!(app.world().get::<ContactForceEventThreshold>(ball).unwrap().0, 0.0);
+    }
+
+    // Two balls pressed together: every active contact is delivered with no threshold, none with a huge one.
+    #[test]
+    fn contact_force_events_are_counted_against_active_contacts() {
+        let run = |threshold: f32| {
+            let mut app = physics_test_app();
+            app.init_resource::<ContactForceEventStats>().add_systems(Update, count_contact_force_events);
+            for (x, push) in [(-BALL_RADIUS, 1.0e3), (BALL_RADIUS, -1.0e3)] {
+                app.world_mut().spawn((
+                    Ball::default(),
+                    RigidBody::Dynamic,
+                    Collider::ball(BALL_RADIUS),
+                    ActiveEvents::CONTACT_FORCE_EVENTS,
+                    ContactForceEventThreshold(threshold),
+                    ExternalForce { force: Vec2::new(push, 0.0), torque: 0.0 },
+                    Transform::from_xyz(x, 0.0, 0.0),
+                ));
+            }
+            for _ in 0..10 {
+                app.update();
+            }
+            *app.world().resource::<ContactForceEventStats>()
+        };
+        let open = run(0.0);
+        assert!(open.delivered > 0 && open.filtered < open.delivered, "{open:?}");
+        let closed = run(f32::MAX);
+        assert_eq!(closed.delivered, 0);
+        assert!(closed.filtered > 0, "{closed:?}");
+    }
+
+    fn peg_layout(arena: ArenaConfig, seed: u64) -> Vec<(u32, PegKind, Vec2)> {
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .insert_resource(RngResource::seeded(seed))
+            .insert_resource(arena)
+            .add_systems(Startup, setup_whirl);
+        app.update();
+        let mut pegs: Vec<(u32, PegKind, Vec2)> = app
+            .world_mut()
+            .query_filtered::<(&PegIndex, &PegKind, &Transform), With<Peg>>()
+            .iter(app.world())
+            .map(|(index, kind, tf)| (index.0, *kind, tf.translation.truncate()))
+            .collect();
+        pegs.sort_by_key(|p| p.0);
+        pegs
+    }
+
+    #[test]
+    fn peg_layout_is_indexed_seeded_and_honours_kinds() {
+        let plain = peg_layout(ArenaConfig::default(), 7);
+        assert!(plain.len() > 100);
+        assert!(plain.iter().enumerate().all(|(i, p)| p.0 == i as u32), "indices follow spawn order");
+        assert!(plain.iter().all(|p| p.1 == PegKind::Inert));
+        assert_eq!(plain, peg_layout(ArenaConfig::default(), 7), "same seed, same layout");
+
+        let arena = ArenaConfig {
+            nutrient_peg_fraction: 0.5,
+            draining_peg_fraction: 0.5,
+            peg_kinds: BTreeMap::from([(3, PegKind::Sticky)]),
+            ..Default::default()
+        };
+        let kinds = peg_layout(arena, 7);
+        assert_eq!(kinds[3].1, PegKind::Sticky);
+        let count = |kind: PegKind| kinds.iter().filter(|p| p.1 == kind).count();
+        assert_eq!(count(PegKind::Inert), 0);
+        assert!(count(PegKind::Nutrient) > 10 && count(PegKind::Draining) > 10);
+    }
+
+    fn vars<'a>(pairs: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| pairs.iter().find(|(k, _)| *k == key).map(|(_, v)| v.to_string())
+    }
+
+    #[test]
+    fn sim_timing_reads_env_and_ignores_bad_values() {
+        assert_eq!(SimTiming::from_env_with(30, vars(&[])), SimTiming { physics_hz: 30.0, substeps: 1, speed: 1.0 });
+        let timing = SimTiming::from_env_with(60, vars(&[("PHYSICS_HZ", "240"), ("PHYSICS_SUBSTEPS", "4"), ("SIM_SPEED", " 0.25 ")]));
+        assert_eq!(timing, SimTiming { physics_hz: 240.0, substeps: 4, speed: 0.25 });
+        assert_eq!(timing.dt(), 1.0 / 240.0);
+        for bad in [("PHYSICS_HZ", "0"), ("PHYSICS_HZ", "-60"), ("PHYSICS_HZ", "inf"), ("PHYSICS_SUBSTEPS", "0"), ("SIM_SPEED", "-1"), ("SIM_SPEED", "NaN"), ("SIM_SPEED", "fast")] {
+            assert_eq!(SimTiming::from_env_with(60, vars(&[bad])), SimTiming { physics_hz: 60.0, substeps: 1, speed: 1.0 }, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn sim_timing_drives_rapier_and_both_clocks() {
+        let mut app = App::new();
+        app.add_plugins(bevy::time::TimePlugin)
+            .init_resource::<TimestepMode>()
+            .insert_resource(SimTiming { physics_hz: 120.0, substeps: 3, speed: 2.0 })
+            .add_systems(Update, apply_sim_timing);
+        app.update();
+        assert_eq!(app.world().resource::<Time<Fixed>>().timestep(), std::time::Duration::from_secs_f64(1.0 / 120.0));
+        assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 2.0);
+        assert!(matches!(*app.world().resource::<TimestepMode>(), TimestepMode::Fixed { substeps: 3, dt } if dt == 1.0 / 120.0));
+
+        // Only changes are pushed: a clock adjusted elsewhere is left alone until SimTiming changes again
+        app.world_mut().resource_mut::<Time<Virtual>>().set_relative_speed(1.0);
+        app.update();
+        assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 1.0);
+        app.world_mut().resource_mut::<SimTiming>().speed = 0.5;
+        app.update();
+        assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 0.5);
+    }
+
+    #[test]
+    fn each_pending_step_runs_one_fixed_tick_while_paused() {
+        #[derive(Resource, Default)]
+        struct Ticks {
+            count: u32,
+            delta: std::time::Duration,
+        }
+        let mut app = App::new();
+        app.add_plugins(bevy::time::TimePlugin)
+            .insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(std::time::Duration::from_secs_f64(1.0 / 60.0)))
+            .init_resource::<PendingSteps>()
+            .init_resource::<Ticks>()
+            .add_systems(PreUpdate, run_pending_steps)
+            .add_systems(FixedUpdate, |mut ticks: ResMut<Ticks>, time: Res<Time>| {
+                ticks.count += 1;
+                ticks.delta = time.delta();
+            });
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        app.update();
+        assert_eq!(app.world().resource::<Ticks>().count, 0, "paused, nothing requested");
+
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        app.world_mut().resource_mut::<PendingSteps>().0 = 5;
+        app.update();
+        let ticks = app.world().resource::<Ticks>();
+        assert_eq!((ticks.count, ticks.delta), (5, timestep), "fixed systems see the fixed clock");
+        assert_eq!(app.world().resource::<Time<Fixed>>().elapsed(), timestep * 5);
+        assert_eq!(app.world().resource::<Time>().elapsed(), app.world().resource::<Time<Virtual>>().elapsed());
+
+        // A large request is spread over frames
+        app.world_mut().resource_mut::<PendingSteps>().0 = MAX_STEPS_PER_FRAME + 10;
+        app.update();
+        assert_eq!(app.world().resource::<Ticks>().count, 5 + MAX_STEPS_PER_FRAME);
+        app.update();
+        assert_eq!(app.world().resource::<Ticks>().count, 5 + MAX_STEPS_PER_FRAME + 10);
+        assert_eq!(app.world().resource::<PendingSteps>().0, 0);
     }
 }
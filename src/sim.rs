@@ -0,0 +1,228 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::arena::ArenaConfig;
+use crate::tuning::AppState;
+
+/// Simulation frame and simulated seconds, published by the Bevy world each physics step
+/// so the HTTP server can stamp records without touching the world.
+#[derive(Resource, Clone, Default)]
+pub struct SimClock {
+    frame: Arc<AtomicU64>,
+    seconds_bits: Arc<AtomicU64>,
+}
+
+impl SimClock {
+    pub fn frame(&self) -> u64 {
+        self.frame.load(Ordering::Relaxed)
+    }
+
+    pub fn seconds(&self) -> f64 {
+        f64::from_bits(self.seconds_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, frame: u64, seconds: f64) {
+        self.frame.store(frame, Ordering::Relaxed);
+        self.seconds_bits.store(seconds.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Upper bound for POST /sim/speed; beyond this the fixed-step loop cannot keep up anyway.
+pub const MAX_SIM_SPEED: f32 = 100.0;
+/// Upper bound for one POST /sim/step.
+pub const MAX_STEPS_PER_REQUEST: u32 = 100_000;
+/// RNG seed for the world built at startup.
+pub const DEFAULT_SEED: u64 = 42;
+
+/// What POST /sim/reset rebuilds the world with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResetSpec {
+    pub seed: u64,
+    /// Replaces the arena layout; `None` rebuilds the current one
+    pub arena: Option<ArenaConfig>,
+}
+
+/// Commands from the HTTP server to the Bevy world.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimCommand {
+    Pause,
+    Resume,
+    /// Run this many physics steps, then stay paused
+    Step(u32),
+    Speed(f32),
+    /// Clear balls, joints, markers and the arena, reseed and rebuild; capture keeps running
+    Reset(ResetSpec),
+}
+
+// Not a Resource; see TuningRx.
+pub struct SimRx(pub mpsc::Receiver<SimCommand>);
+
+/// Run state as seen by API clients.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimStatus {
+    pub paused: bool,
+    /// Simulated seconds per video second
+    pub speed: f32,
+    /// Steps requested with /sim/step that have not run yet
+    pub pending_steps: u32,
+    pub frame: u64,
+    pub sim_seconds: f64,
+    /// Seed the current world was built with
+    pub seed: u64,
+    /// Resets since startup
+    pub resets: u32,
+}
+
+impl Default for SimStatus {
+    fn default() -> Self {
+        Self { paused: false, speed: 1.0, pending_steps: 0, frame: 0, sim_seconds: 0.0, seed: DEFAULT_SEED, resets: 0 }
+    }
+}
+
+/// Run state published by the Bevy world for GET /sim.
+#[derive(Resource, Clone)]
+pub struct SimStatusMirror(pub Arc<Mutex<SimStatus>>);
+
+/// Server-side handle to the sim control channel and mirror.
+#[derive(Clone)]
+pub struct SimApi {
+    tx: mpsc::Sender<SimCommand>,
+    mirror: Arc<Mutex<SimStatus>>,
+}
+
+impl SimApi {
+    /// Create the server handle plus the receiver and mirror the Bevy world needs.
+    pub fn channel() -> (Self, SimRx, SimStatusMirror) {
+        let (tx, rx) = mpsc::channel();
+        let mirror = Arc::new(Mutex::new(SimStatus::default()));
+        (Self { tx, mirror: mirror.clone() }, SimRx(rx), SimStatusMirror(mirror))
+    }
+
+    // Send, then reflect the command in the mirror right away; the world republishes once applied
+    fn send(&self, cmd: SimCommand) -> Result<SimStatus, (StatusCode, String)> {
+        let mut status = self.mirror.lock().unwrap();
+        self.tx
+            .send(cmd.clone())
+            .map_err(|_| (StatusCode::SERVICE_UNAVAILABLE, "simulation is not running".to_string()))?;
+        match cmd {
+            SimCommand::Pause => status.paused = true,
+            SimCommand::Resume => {
+                status.paused = false;
+                status.pending_steps = 0;
+            }
+            SimCommand::Step(n) => status.pending_steps = status.pending_steps.saturating_add(n),
+            SimCommand::Speed(speed) => status.speed = speed,
+            SimCommand::Reset(spec) => {
+                status.pending_steps = 0;
+                status.seed = spec.seed;
+                status.resets += 1;
+            }
+        }
+        Ok(*status)
+    }
+}
+
+type SimResult = Result<Json<SimStatus>, (StatusCode, String)>;
+
+async fn get_sim(State(api): State<SimApi>) -> Json<SimStatus> {
+    Json(*api.mirror.lock().unwrap())
+}
+
+async fn pause(State(api): State<SimApi>) -> SimResult {
+    api.send(SimCommand::Pause).map(Json)
+}
+
+async fn resume(State(api): State<SimApi>) -> SimResult {
+    api.send(SimCommand::Resume).map(Json)
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct StepQuery {
+    pub n: Option<u32>,
+}
+
+// Steps only make sense on a paused simulation; a running one would swallow them.
+async fn step(State(api): State<SimApi>, Query(q): Query<StepQuery>) -> SimResult {
+    let n = q.n.unwrap_or(1);
+    if n == 0 || n > MAX_STEPS_PER_REQUEST {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("n must be 1..={MAX_STEPS_PER_REQUEST}")));
+    }
+    if !api.mirror.lock().unwrap().paused {
+        return Err((StatusCode::CONFLICT, "pause the simulation before stepping".to_string()));
+    }
+    api.send(SimCommand::Step(n)).map(Json)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SpeedRequest {
+    pub speed: f32,
+}
+
+async fn speed(State(api): State<SimApi>, Json(req): Json<SpeedRequest>) -> SimResult {
+    if !(req.speed > 0.0 && req.speed <= MAX_SIM_SPEED) {
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, format!("speed must be in (0, {MAX_SIM_SPEED}]; use /sim/pause to stop")));
+    }
+    api.send(SimCommand::Speed(req.speed)).map(Json)
+}
+
+/// Body of POST /sim/reset; every field is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResetRequest {
+    /// Random when omitted; the chosen seed is reported in the response
+    pub seed: Option<u64>,
+    /// Tuning preset to apply before rebuilding
+    pub preset: Option<String>,
+    /// New arena layout; omitted fields take their defaults
+    pub arena: Option<ArenaConfig>,
+}
+
+// The tuning preset goes through the normal tuning path (validated, logged in history) before the
+// rebuild is queued, so the new world starts under it. If the rebuild cannot be queued the
+// previous tuning goes back, so a failed reset changes nothing.
+async fn reset(State(state): State<AppState>, body: Option<Json<ResetRequest>>) -> SimResult {
+    let req = body.map(|Json(r)| r).unwrap_or_default();
+    if let Some(arena) = &req.arena {
+        arena.validate().map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, e))?;
+    }
+    let mut before = None;
+    if let Some(name) = &req.preset {
+        let tuning = state.presets().load(name).map_err(|e| (e.status(), e.to_string()))?;
+        let previous = state.current_tuning();
+        state
+            .apply_tuning(&format!("reset preset {name}"), tuning)
+            .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("preset {name}: {}", e.error)))?;
+        before = Some((name, previous));
+    }
+    let seed = req.seed.unwrap_or_else(rand::random);
+    eprintln!("[diag] sim reset seed={seed} preset={:?} arena={}", req.preset, if req.arena.is_some() { "new" } else { "same" });
+    let sent = state.sim().send(SimCommand::Reset(ResetSpec { seed, arena: req.arena }));
+    if let (Err(_), Some((name, previous))) = (&sent, before) {
+        // It passed validation when it was the current tuning, so this cannot fail
+        let _ = state.apply_tuning(&format!("reset preset {name} rolled back"), previous);
+    }
+    sent.map(Json)
+}
+
+/// Routes that need more than the sim channel (presets, tuning); merged before the state is attached.
+pub fn reset_routes() -> Router<AppState> {
+    Router::new().route("/sim/reset", post(reset))
+}
+
+pub fn sim_routes(api: SimApi) -> Router {
+    Router::new()
+        .route("/sim", get(get_sim))
+        .route("/sim/pause", post(pause))
+        .route("/sim/resume", post(resume))
+        .route("/sim/step", post(step))
+        .route("/sim/speed", post(speed))
+        .with_state(api)
+}
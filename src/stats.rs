@@ -0,0 +1,152 @@
+use std::time::Instant;
+
+use bevy::prelude::{
+    App, EventReader, FixedFirst, FixedLast, IntoScheduleConfigs, Plugin, Query, Real, Res, ResMut, Resource, Time,
+    Timer, TimerMode, Update, With,
+};
+
+use crate::{
+    ball::{Ball, JointBorn},
+    events::{BallBorn, BallDied, Bitten},
+    metrics::{Metrics, Summary, WorldMetrics},
+    sim::SimClock,
+    stream::{LiveFeed, LiveMessage, StatsDigest},
+};
+
+/// Running totals of ball births, deaths and bites since startup, counted from the lifecycle
+/// events; balls cleared by a world reset are not deaths.
+#[derive(Resource, Default)]
+pub struct PopulationCounters {
+    pub births: u64,
+    pub deaths: u64,
+    pub bites: u64,
+}
+
+/// Wall-clock time spent in the fixed-step schedule (physics plus the ball systems).
+#[derive(Resource, Default)]
+struct StepTiming {
+    started: Option<Instant>,
+    sum_seconds: f64,
+    count: u64,
+    last_seconds: f64,
+}
+
+#[derive(Resource)]
+struct MetricsTimer(Timer);
+
+#[derive(Resource)]
+struct DigestTimer {
+    timer: Timer,
+    last_births: u64,
+    last_deaths: u64,
+    last_sim_seconds: f64,
+}
+
+fn count_births_and_deaths(
+    mut born: EventReader<BallBorn>,
+    mut died: EventReader<BallDied>,
+    mut bitten: EventReader<Bitten>,
+    mut counters: ResMut<PopulationCounters>,
+) {
+    counters.births += born.read().count() as u64;
+    counters.deaths += died.read().count() as u64;
+    counters.bites += bitten.read().count() as u64;
+}
+
+fn start_step_timer(mut timing: ResMut<StepTiming>) {
+    timing.started = Some(Instant::now());
+}
+
+fn end_step_timer(mut timing: ResMut<StepTiming>) {
+    let Some(started) = timing.started.take() else { return; };
+    let seconds = started.elapsed().as_secs_f64();
+    timing.sum_seconds += seconds;
+    timing.count += 1;
+    timing.last_seconds = seconds;
+}
+
+// Refresh GET /metrics once per real second, paused or not; percentiles need a sort over every ball
+#[allow(clippy::too_many_arguments)]
+fn publish_metrics(
+    real: Res<Time<Real>>,
+    mut timer: ResMut<MetricsTimer>,
+    metrics: Option<Res<Metrics>>,
+    counters: Res<PopulationCounters>,
+    timing: Res<StepTiming>,
+    clock: Res<SimClock>,
+    q_balls: Query<&Ball>,
+    q_joints: Query<(), With<JointBorn>>,
+) {
+    if !timer.0.tick(real.delta()).just_finished() {
+        return;
+    }
+    let Some(metrics) = metrics else { return; };
+    let (ages, life): (Vec<f64>, Vec<f64>) = q_balls.iter().map(|b| (b.age as f64, b.life_points as f64)).unzip();
+    metrics.publish(WorldMetrics {
+        frame: clock.frame(),
+        sim_seconds: clock.seconds(),
+        population: ages.len() as u64,
+        joints: q_joints.iter().count() as u64,
+        births: counters.births,
+        deaths: counters.deaths,
+        bites: counters.bites,
+        age: Summary::of(ages),
+        life_points: Summary::of(life),
+        step_seconds_sum: timing.sum_seconds,
+        step_count: timing.count,
+        last_step_seconds: timing.last_seconds,
+    });
+}
+
+// Once per simulated second, push a digest to live stream clients
+fn publish_stats_digest(
+    time: Res<Time>,
+    mut digest: ResMut<DigestTimer>,
+    counters: Res<PopulationCounters>,
+    clock: Res<SimClock>,
+    feed: Option<Res<LiveFeed>>,
+    q_balls: Query<(), With<Ball>>,
+    q_joints: Query<(), With<JointBorn>>,
+) {
+    if !digest.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let sim_seconds = clock.seconds();
+    let dt = (sim_seconds - digest.last_sim_seconds).max(f64::EPSILON);
+    let msg = StatsDigest {
+        frame: clock.frame(),
+        sim_seconds,
+        population: q_balls.iter().count(),
+        joints: q_joints.iter().count(),
+        births: counters.births,
+        deaths: counters.deaths,
+        births_per_sec: (counters.births - digest.last_births) as f64 / dt,
+        deaths_per_sec: (counters.deaths - digest.last_deaths) as f64 / dt,
+    };
+    digest.last_births = counters.births;
+    digest.last_deaths = counters.deaths;
+    digest.last_sim_seconds = sim_seconds;
+    if let Some(feed) = feed {
+        feed.publish(LiveMessage::Stats(msg));
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PopulationCounters>()
+            .init_resource::<SimClock>()
+            .insert_resource(DigestTimer {
+                timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+                last_births: 0,
+                last_deaths: 0,
+                last_sim_seconds: 0.0,
+            })
+            .init_resource::<StepTiming>()
+            .insert_resource(MetricsTimer(Timer::from_seconds(1.0, TimerMode::Repeating)))
+            .add_systems(FixedFirst, start_step_timer)
+            .add_systems(FixedLast, end_step_timer)
+            .add_systems(Update, (count_births_and_deaths, publish_stats_digest, publish_metrics).chain());
+    }
+}
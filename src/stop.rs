@@ -0,0 +1,143 @@
+use std::sync::atomic::Ordering;
+
+use bevy::{
+    diagnostic::FrameCount,
+    prelude::{App, AppExit, EventWriter, FixedPostUpdate, Local, Plugin, Query, Res, Update},
+};
+
+use crate::{
+    ball::Ball,
+    query::ScentCounts,
+    setup::SimReset,
+    sim::SimClock,
+    termination::{ExitReason, RunOutcome, StopConditions, WorldSample},
+    AllowExitFlag,
+};
+
+// Whether the current world has had a live ball, and which reset it was built by.
+#[derive(Default)]
+struct LifeSeen {
+    had_life: bool,
+    resets: u32,
+}
+
+// Checked after every physics step so frame and time limits land exactly. Shutdown goes through
+// AllowExitFlag like Ctrl+C, so the MP4 is finished the same way; windowed runs just exit.
+#[allow(clippy::too_many_arguments)]
+fn check_stop_conditions(
+    stop: Res<StopConditions>,
+    outcome: Res<RunOutcome>,
+    clock: Option<Res<SimClock>>,
+    flag: Option<Res<AllowExitFlag>>,
+    mut exit: EventWriter<AppExit>,
+    q_balls: Query<&Ball>,
+    reset: Option<Res<SimReset>>,
+    mut seen: Local<LifeSeen>,
+    mut scents: Local<Vec<[f32; 2]>>,
+    mut counts: Local<ScentCounts>,
+) {
+    if outcome.reason().is_some() {
+        return;
+    }
+    // A reset clears the world without anything dying, so the new one has to come alive again
+    let resets = reset.map_or(0, |r| r.count);
+    if resets != seen.resets {
+        *seen = LifeSeen { had_life: false, resets };
+    }
+    scents.clear();
+    scents.extend(q_balls.iter().map(|b| b.genome_friendly_scent.to_array()));
+    seen.had_life |= !scents.is_empty();
+    let (frame, sim_seconds) = clock.map_or((0, 0.0), |c| (c.frame(), c.seconds()));
+    let Some(reason) = stop.check(&WorldSample { frame, sim_seconds, scents: &scents }, seen.had_life, &mut counts) else { return; };
+    eprintln!("[diag] stop condition met at frame {frame}: {reason:?}; requesting shutdown...");
+    request_shutdown(reason, &outcome, flag, &mut exit);
+}
+
+// --duration counts rendered frames, which keep coming while the simulation is paused.
+fn check_video_duration(
+    stop: Res<StopConditions>,
+    outcome: Res<RunOutcome>,
+    count: Res<FrameCount>,
+    flag: Option<Res<AllowExitFlag>>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if outcome.reason().is_some() {
+        return;
+    }
+    let Some(reason) = stop.check_video(count.0 as u64) else { return; };
+    eprintln!("[diag] duration reached after {} frames; requesting shutdown...", count.0);
+    request_shutdown(reason, &outcome, flag, &mut exit);
+}
+
+fn request_shutdown(reason: ExitReason, outcome: &RunOutcome, flag: Option<Res<AllowExitFlag>>, exit: &mut EventWriter<AppExit>) {
+    outcome.record(reason);
+    match flag {
+        Some(flag) => flag.0.store(true, Ordering::SeqCst),
+        None => {
+            exit.write(AppExit::Success);
+        }
+    }
+}
+
+/// Ends the run when one of `conditions` is met; does nothing when none are set. The reason goes
+/// to the app's RunOutcome, which must already be inserted.
+pub struct StopPlugin {
+    pub conditions: StopConditions,
+}
+
+impl Plugin for StopPlugin {
+    fn build(&self, app: &mut App) {
+        if self.conditions.is_empty() {
+            return;
+        }
+        app.insert_resource(self.conditions.clone())
+            .add_systems(FixedPostUpdate, check_stop_conditions)
+            .add_systems(Update, check_video_duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::{Entity, With};
+
+    use super::*;
+
+    fn extinction_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<RunOutcome>()
+            .init_resource::<SimReset>()
+            .init_resource::<FrameCount>()
+            .add_plugins(StopPlugin { conditions: StopConditions { extinction: true, ..Default::default() } });
+        app
+    }
+
+    fn step(app: &mut App) -> Option<ExitReason> {
+        app.world_mut().run_schedule(FixedPostUpdate);
+        app.world().resource::<RunOutcome>().reason()
+    }
+
+    fn kill_all(app: &mut App) {
+        let balls: Vec<Entity> = app.world_mut().query_filtered::<Entity, With<Ball>>().iter(app.world()).collect();
+        for ball in balls {
+            app.world_mut().despawn(ball);
+        }
+    }
+
+    #[test]
+    fn a_reset_world_must_come_alive_before_it_can_go_extinct() {
+        let mut app = extinction_app();
+        assert_eq!(step(&mut app), None, "empty before the first spawn");
+        app.world_mut().spawn(Ball::default());
+        assert_eq!(step(&mut app), None);
+
+        // The reset clears every ball and the rebuilt world starts empty
+        kill_all(&mut app);
+        app.world_mut().resource_mut::<SimReset>().count += 1;
+        assert_eq!(step(&mut app), None, "a reset is not an extinction");
+
+        app.world_mut().spawn(Ball::default());
+        assert_eq!(step(&mut app), None);
+        kill_all(&mut app);
+        assert_eq!(step(&mut app), Some(ExitReason::Extinction));
+    }
+}
@@ -0,0 +1,96 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use bevy::prelude::Resource;
+use futures_util::stream::{self, Stream};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::tuning::{ApiTuning, AppState};
+
+/// Periodic summary of the running simulation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsDigest {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    pub population: usize,
+    pub joints: usize,
+    /// Totals since startup
+    pub births: u64,
+    pub deaths: u64,
+    /// Rates over the last digest interval, per simulated second
+    pub births_per_sec: f64,
+    pub deaths_per_sec: f64,
+}
+
+/// One message on GET /live; the SSE event name matches `type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveMessage {
+    Tuning { tuning: ApiTuning },
+    Stats(StatsDigest),
+}
+
+impl LiveMessage {
+    fn event_name(&self) -> &'static str {
+        match self {
+            Self::Tuning { .. } => "tuning",
+            Self::Stats(_) => "stats",
+        }
+    }
+}
+
+/// Fan-out of live messages to every connected client. Slow clients skip what they missed.
+#[derive(Resource, Clone)]
+pub struct LiveFeed {
+    tx: broadcast::Sender<LiveMessage>,
+}
+
+impl Default for LiveFeed {
+    fn default() -> Self {
+        let (tx, _) = broadcast::channel(256);
+        Self { tx }
+    }
+}
+
+impl LiveFeed {
+    /// Dropped silently when nobody is listening.
+    pub fn publish(&self, msg: LiveMessage) {
+        let _ = self.tx.send(msg);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveMessage> {
+        self.tx.subscribe()
+    }
+}
+
+fn to_event(msg: &LiveMessage) -> Event {
+    let data = serde_json::to_string(msg).unwrap_or_default();
+    Event::default().event(msg.event_name()).data(data)
+}
+
+// Current tuning first, so a fresh client never has to poll, then everything published after it.
+async fn live(State(state): State<AppState>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let rx = state.feed().subscribe();
+    let first = LiveMessage::Tuning { tuning: ApiTuning::from(&state.current_tuning()) };
+    let updates = stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(msg) => return Some((Ok(to_event(&msg)), rx)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    let stream = futures_util::StreamExt::chain(stream::once(async move { Ok(to_event(&first)) }), updates);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+pub fn live_routes() -> Router<AppState> {
+    Router::new().route("/live", get(live))
+}
@@ -0,0 +1,215 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::Flags;
+use crate::query::{dominant_scent_with, DominantScent, ScentCounts};
+
+/// Dominance is only judged once at least this many balls are alive, so a handful of
+/// survivors sharing a scent does not end a run.
+pub const DOMINANCE_MIN_POPULATION: u64 = 20;
+
+/// Why a run ended, as written to `<recording>.exit.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum ExitReason {
+    MaxFrames { limit: u64 },
+    MaxSimSeconds { limit: f64 },
+    /// Every ball died after some had lived
+    Extinction,
+    Overpopulation { population: u64, limit: u64 },
+    /// One scent bucket (see crate::query::SCENT_BUCKET) holds at least `limit` of the population
+    Dominance { scent: [f32; 2], share: f64, count: usize, limit: f64 },
+    /// `--duration` of video time elapsed, in rendered frames
+    Duration { frames: u64 },
+    /// Ctrl+C
+    Interrupted,
+    StdinClosed,
+}
+
+/// What the stop conditions look at, sampled after each physics step.
+#[derive(Debug, Clone)]
+pub struct WorldSample<'a> {
+    pub frame: u64,
+    pub sim_seconds: f64,
+    /// Friendly scent of every live ball
+    pub scents: &'a [[f32; 2]],
+}
+
+/// Conditions that end a run on their own; all off by default.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct StopConditions {
+    pub max_frames: Option<u64>,
+    pub max_sim_seconds: Option<f64>,
+    pub extinction: bool,
+    pub max_population: Option<u64>,
+    /// Share of the population in one scent bucket, in (0.5, 1]
+    pub dominance: Option<f64>,
+    /// Rendered frames, from `--duration` at the video frame rate; set by crate::cli::Cli
+    pub max_video_frames: Option<u64>,
+}
+
+impl StopConditions {
+    /// `--max-frames`, `--max-sim-seconds`, `--stop-on-extinction`, `--max-population` and
+    /// `--dominance`, falling back to `MAX_FRAMES`, `MAX_SIM_SECONDS`, `STOP_ON_EXTINCTION`,
+    /// `MAX_POPULATION` and `STOP_DOMINANCE`.
+    pub fn load_with_env(flags: &Flags, env: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let value = |flag: &str, var: &str| flags.last(flag).map(str::to_string).or_else(|| env(var)).filter(|v| !v.is_empty());
+        fn parse<T: std::str::FromStr>(raw: Option<String>, what: &str, ok: impl Fn(&T) -> bool) -> Result<Option<T>, String> {
+            raw.map(|raw| raw.parse().ok().filter(|v| ok(v)).ok_or_else(|| format!("{what}, got {raw:?}"))).transpose()
+        }
+        let extinction = flags.has("--stop-on-extinction")
+            || env("STOP_ON_EXTINCTION").is_some_and(|v| !matches!(v.trim().to_ascii_lowercase().as_str(), "" | "0" | "false" | "no" | "off"));
+        Ok(Self {
+            max_frames: parse(value("--max-frames", "MAX_FRAMES"), "max frames must be a positive whole number", |n: &u64| *n > 0)?,
+            max_sim_seconds: parse(value("--max-sim-seconds", "MAX_SIM_SECONDS"), "max sim seconds must be positive", |s: &f64| {
+                s.is_finite() && *s > 0.0
+            })?,
+            extinction,
+            max_population: parse(value("--max-population", "MAX_POPULATION"), "max population must be a positive whole number", |n: &u64| *n > 0)?,
+            dominance: parse(value("--dominance", "STOP_DOMINANCE"), "dominance must be a share in (0.5, 1]", |s: &f64| {
+                *s > 0.5 && *s <= 1.0
+            })?,
+            max_video_frames: None,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The first condition met, checked in the order listed in `--help`. `had_life` says whether
+    /// any ball has been alive yet, so an empty world before the first spawn is not an extinction.
+    /// `counts` is scratch space for the dominance check.
+    pub fn check(&self, sample: &WorldSample, had_life: bool, counts: &mut ScentCounts) -> Option<ExitReason> {
+        let population = sample.scents.len() as u64;
+        if let Some(limit) = self.max_frames.filter(|l| sample.frame >= *l) {
+            return Some(ExitReason::MaxFrames { limit });
+        }
+        if let Some(limit) = self.max_sim_seconds.filter(|l| sample.sim_seconds >= *l) {
+            return Some(ExitReason::MaxSimSeconds { limit });
+        }
+        if self.extinction && had_life && population == 0 {
+            return Some(ExitReason::Extinction);
+        }
+        if let Some(limit) = self.max_population.filter(|l| population > *l) {
+            return Some(ExitReason::Overpopulation { population, limit });
+        }
+        if let Some(limit) = self.dominance.filter(|_| population >= DOMINANCE_MIN_POPULATION) {
+            if let Some(DominantScent { scent, count, share }) = dominant_scent_with(counts, sample.scents).filter(|d| d.share >= limit) {
+                return Some(ExitReason::Dominance { scent, share, count, limit });
+            }
+        }
+        None
+    }
+
+    /// The video-time limit, checked once per rendered frame so it also runs out while paused.
+    pub fn check_video(&self, video_frame: u64) -> Option<ExitReason> {
+        self.max_video_frames.filter(|l| video_frame >= *l).map(|frames| ExitReason::Duration { frames })
+    }
+}
+
+/// The exit reason file: why and when the run stopped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExitRecord {
+    #[serde(flatten)]
+    pub reason: ExitReason,
+    pub frame: u64,
+    pub sim_seconds: f64,
+}
+
+impl ExitRecord {
+    /// Write the record as JSON, making its directory if needed.
+    pub fn write(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, text + "\n")
+    }
+}
+
+/// The first reason a shutdown was requested; later triggers (e.g. Ctrl+C while the MP4 finishes) are ignored.
+#[derive(Resource, Clone, Default)]
+pub struct RunOutcome(Arc<Mutex<Option<ExitReason>>>);
+
+impl RunOutcome {
+    /// Returns false when a reason was already recorded.
+    pub fn record(&self, reason: ExitReason) -> bool {
+        let mut slot = self.0.lock().unwrap();
+        if slot.is_some() {
+            return false;
+        }
+        *slot = Some(reason);
+        true
+    }
+
+    pub fn reason(&self) -> Option<ExitReason> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(line: &str) -> Flags {
+        let args: Vec<String> = std::iter::once("live-whirl").chain(line.split_whitespace()).map(str::to_string).collect();
+        Flags::parse(&args).unwrap()
+    }
+
+    #[test]
+    fn loads_flags_and_env() {
+        let env = |key: &str| match key {
+            "MAX_FRAMES" => Some("100".to_string()),
+            "STOP_ON_EXTINCTION" => Some("1".to_string()),
+            _ => None,
+        };
+        let stop = StopConditions::load_with_env(&flags("--max-frames 50 --dominance 0.8"), env).unwrap();
+        assert_eq!(stop.max_frames, Some(50));
+        assert!(stop.extinction);
+        assert_eq!(stop.dominance, Some(0.8));
+        assert!(StopConditions::load_with_env(&flags(""), |_| None).unwrap().is_empty());
+        for bad in ["--max-frames 0", "--dominance 0.4", "--max-sim-seconds nan", "--max-population x"] {
+            assert!(StopConditions::load_with_env(&flags(bad), |_| None).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn checks_conditions_in_order() {
+        let stop = StopConditions { max_frames: Some(10), extinction: true, max_population: Some(3), dominance: Some(0.9), ..Default::default() };
+        fn sample(frame: u64, scents: &[[f32; 2]]) -> WorldSample<'_> {
+            WorldSample { frame, sim_seconds: 0.0, scents }
+        }
+        let mut counts = ScentCounts::new();
+        assert_eq!(stop.check(&sample(10, &[]), true, &mut counts), Some(ExitReason::MaxFrames { limit: 10 }));
+        assert_eq!(stop.check(&sample(1, &[]), false, &mut counts), None);
+        assert_eq!(stop.check(&sample(1, &[]), true, &mut counts), Some(ExitReason::Extinction));
+        assert_eq!(stop.check(&sample(1, &[[0.0, 0.0]; 4]), true, &mut counts), Some(ExitReason::Overpopulation { population: 4, limit: 3 }));
+        assert_eq!(stop.check_video(1_000), None, "no duration set");
+        let timed = StopConditions { max_video_frames: Some(120), ..Default::default() };
+        assert_eq!((timed.check_video(119), timed.check_video(120)), (None, Some(ExitReason::Duration { frames: 120 })));
+
+        let stop = StopConditions { dominance: Some(0.9), ..Default::default() };
+        let mut scents = vec![[0.5, 0.5]; 19];
+        assert_eq!(stop.check(&sample(1, &scents), true, &mut counts), None, "too few balls to judge");
+        scents.push([0.52, 0.49]);
+        let Some(ExitReason::Dominance { scent, count, .. }) = stop.check(&sample(1, &scents), true, &mut counts) else { panic!("expected dominance") };
+        assert_eq!((scent, count), ([0.5, 0.5], 20));
+        scents.extend([[-1.0, -1.0]; 3]);
+        assert_eq!(stop.check(&sample(1, &scents), true, &mut counts), None);
+    }
+
+    #[test]
+    fn first_reason_wins_and_record_round_trips() {
+        let outcome = RunOutcome::default();
+        assert!(outcome.record(ExitReason::Extinction));
+        assert!(!outcome.record(ExitReason::Interrupted));
+        let record = ExitRecord { reason: outcome.reason().unwrap(), frame: 7, sim_seconds: 0.5 };
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["reason"], "extinction");
+        assert_eq!(serde_json::from_value::<ExitRecord>(json).unwrap(), record);
+    }
+}
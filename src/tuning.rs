@@ -1,390 +1,506 @@
 use std::net::SocketAddr;
 use std::sync::{mpsc, Arc, Mutex};
 
-use axum::{extract::State, routing::get, Json, Router};
+use axum::{extract::State, http::StatusCode, routing::{get, post}, Json, Router};
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use tokio::runtime::Builder;
+use tuning_derive::TuningApi;
 
 use bevy::prelude::{Resource, Res, ResMut};
 
-// Hierarchical API structs for request/response JSON
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiTuning {
-    pub stickiness: ApiStickiness,
-    pub energy_share: ApiEnergyShare,
-    pub bite: ApiBite,
-    pub max_age: ApiMaxAge,
-    pub reproduction: ApiReproduction,
-    pub labels: ApiLabels,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiStickiness {
-    pub stick_range: ApiStickRange,
-    pub break_threshold: f32,
-    // Used to derive a Rapier contact force event threshold from rel_vel_min
-    // Effective multiplier = (1.0 - contact_force_performance_cutoff_fudge_factor)
+use crate::pegs_api::{peg_routes, PegsApi};
+use crate::balls::{balls_routes, BallsApi};
+use crate::event_log::{event_routes, EventFeed};
+use crate::history::{ChangeStamp, TuningChange, TuningHistory};
+use crate::http::HttpConfig;
+use crate::metrics::{metrics_routes, Metrics};
+use crate::panel::panel_routes;
+use crate::presets::{preset_routes, PresetStore};
+use crate::query::{query_routes, QueryApi};
+use crate::schedule::{schedule_routes, TuningSchedule};
+use crate::sim::{reset_routes, sim_routes, SimApi, SimClock};
+use crate::stream::{live_routes, LiveFeed, LiveMessage};
+
+// The hierarchical API types (ApiTuning, ApiTuningUpdate and one pair per group), both conversions
+// and Default are generated from the attributes below by tuning-derive. Adding a tunable is one
+// field here. `range` feeds both GET /tuning/schema and validate(); checks that span fields
+// (min below max, and so on) are written out in validate().
+#[derive(Debug, Clone, Serialize, Deserialize, Resource, TuningApi)]
+#[tuning(api = "ApiTuning", update = "ApiTuningUpdate", doc = "Live simulation tuning, grouped by subsystem.")]
+#[tuning(group(path = "stickiness", name = "Stickiness", doc = "When colliding balls stick together, and when joints break."))]
+#[tuning(group(path = "stickiness.stick_range", name = "StickRange", doc = "Relative speed window (pixels/s) in which a ball-to-ball contact forms a joint."))]
+#[tuning(group(path = "stickiness.fatigue", name = "JointFatigue", doc = "Joint weakening: strength = max(joint_min_strength, 1 - age_frames * age_rate - impulse * impulse_rate)."))]
+#[tuning(group(path = "energy_share", name = "EnergyShare", doc = "Life point sharing between jointed balls."))]
+#[tuning(group(path = "energy_share.genome_friendly_distance_range", name = "GenomeFriendlyDistanceRange", doc = "Scent distance under which two balls are friendly; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "energy_share.energy_share_hostile_rand_range", name = "EnergyShareHostileRandRange", doc = "Fraction taken by the stronger of two hostile balls, drawn from [min, max), min < max."))]
+#[tuning(group(path = "energy_share.genome_energy_share_range", name = "GenomeEnergyShareRange", doc = "Fraction of life points a parent gives each child; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "bite", name = "Bite", doc = "Hostile balls biting life points from each other on hard contacts."))]
+#[tuning(group(path = "bite.genome_bite_size_range", name = "GenomeBiteSizeRange", doc = "Life points per bite; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "max_age", name = "MaxAge", doc = "Aging."))]
+#[tuning(group(path = "max_age.genome_max_age_range", name = "GenomeMaxAgeRange", doc = "Age in life ticks after which survival costs apply; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "reproduction", name = "Reproduction", doc = "Budding of child balls."))]
+#[tuning(group(path = "reproduction.genome_reproduction_rate_range", name = "GenomeReproductionRateRange", doc = "Chance per reproduction tick that a ball buds; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "reproduction.genome_safe_reproduction_points_range", name = "GenomeSafeReproductionPointsRange", doc = "Life points a ball needs before it buds; new genomes draw from [min, max), min < max."))]
+#[tuning(group(path = "pegs", name = "Pegs", doc = "Strength of interactive pegs (see ArenaConfig for which pegs are which kind)."))]
+#[tuning(group(path = "labels", name = "Labels", doc = "On-screen debug labels."))]
+#[tuning(group(path = "labels.collision", name = "CollisionLabels", doc = "Contact force labels; collision_label_force_min is in display units (force / pixels per meter)."))]
+#[tuning(group(path = "labels.break", name = "BreakLabels", field = "break_labels", doc = "Joint break labels; break_label_impulse_min is in raw impulse units."))]
+#[tuning(group(path = "labels.age", name = "AgeLabels", doc = "Age labels, shown for balls whose age is in the range."))]
+#[tuning(group(path = "labels.age.age_label_range", name = "AgeLabelRange", doc = "Inclusive; min <= max."))]
+#[tuning(group(path = "labels.energy", name = "EnergyLabels", doc = "Life point labels, shown for balls whose life points are in the range."))]
+#[tuning(group(path = "labels.energy.energy_label_range", name = "EnergyLabelRange", doc = "Inclusive; min <= max."))]
+pub struct PhysicsTuning {
+    #[tuning(path = "stickiness.stick_range.rel_vel_min", default = 0.15, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub rel_vel_min: f32,
+    #[tuning(path = "stickiness.stick_range.rel_vel_max", default = 360.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub rel_vel_max: f32,
+    /// Joint impulse above which a joint breaks (raw Rapier impulse units), before fatigue
+    #[tuning(path = "stickiness.break_threshold", default = 360.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub break_force_threshold: f32,
+    /// Margin below the rel_vel_min-derived contact force threshold (which is scaled by 1 - this); contacts under the threshold never reach the sim
+    #[tuning(path = "stickiness.contact_force_performance_cutoff_fudge_factor", default = 0.00001, range(min = 0.0, max = 1.0))]
     pub contact_force_performance_cutoff_fudge_factor: f32,
-}
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiStickRange { pub rel_vel_min: f32, pub rel_vel_max: f32 }
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiEnergyShare {
+    /// Strength lost per physics frame of joint age
+    #[tuning(path = "stickiness.fatigue.joint_age_fatigue_rate", default = 0.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub joint_age_fatigue_rate: f32,
+    /// Strength lost per unit of impulse the joint has absorbed
+    #[tuning(path = "stickiness.fatigue.joint_impulse_fatigue_rate", default = 0.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub joint_impulse_fatigue_rate: f32,
+    /// Floor on joint strength (fraction of break_threshold)
+    #[tuning(path = "stickiness.fatigue.joint_min_strength", default = 0.25, range(min = 0.0, max = 1.0))]
+    pub joint_min_strength: f32,
+    /// Life points charged to both ends of each joint per life tick
+    #[tuning(path = "stickiness.fatigue.joint_upkeep_cost_per_tick", default = 0)]
+    pub joint_upkeep_cost_per_tick: u32,
+
+    #[tuning(path = "energy_share.energy_transfer_enabled", default = true)]
     pub energy_transfer_enabled: bool,
+    /// Life point difference under which friendly pairs share at energy_share_friendly_rate
+    #[tuning(path = "energy_share.energy_share_diff_threshold", default = 100)]
     pub energy_share_diff_threshold: u32,
+    /// New balls draw each scent coordinate from (-range, range); must be > 0
+    #[tuning(path = "energy_share.genome_friendly_scent_range", default = 1.0, range(min = "f32::MIN_POSITIVE", max = "MAX_TUNING_VALUE"))]
     pub genome_friendly_scent_range: f32,
-    pub genome_friendly_distance_range: ApiGenomeFriendlyDistanceRange,
+    #[tuning(path = "energy_share.genome_friendly_distance_range.genome_friendly_distance_min", default = 0.15, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub genome_friendly_distance_min: f32,
+    #[tuning(path = "energy_share.genome_friendly_distance_range.genome_friendly_distance_max", default = 1.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub genome_friendly_distance_max: f32,
+    /// Fraction shared between two friendly balls
+    #[tuning(path = "energy_share.energy_share_friendly_rate", default = 0.5, range(min = 0.0, max = 1.0))]
     pub energy_share_friendly_rate: f32,
+    /// Fraction shared when only the child considers the parent friendly
+    #[tuning(path = "energy_share.energy_share_parent_not_friendly_child_friendly_rate", default = 0.75, range(min = 0.0, max = 1.0))]
     pub energy_share_parent_not_friendly_child_friendly_rate: f32,
+    /// Fraction shared when only the parent considers the child friendly
+    #[tuning(path = "energy_share.energy_share_parent_friendly_child_not_friendly_rate", default = 0.25, range(min = 0.0, max = 1.0))]
     pub energy_share_parent_friendly_child_not_friendly_rate: f32,
-    pub energy_share_hostile_rand_range: ApiEnergyShareHostileRandRange,
-    pub genome_energy_share_range: ApiGenomeEnergyShareRange,
-}
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeFriendlyDistanceRange { pub genome_friendly_distance_min: f32, pub genome_friendly_distance_max: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiEnergyShareHostileRandRange { pub energy_share_hostile_rand_min: f32, pub energy_share_hostile_rand_max: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeEnergyShareRange { pub genome_energy_share_min: f32, pub genome_energy_share_max: f32 }
+    #[tuning(path = "energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_min", default = 0.5, range(min = 0.0, max = 1.0))]
+    pub energy_share_hostile_rand_min: f32,
+    #[tuning(path = "energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_max", default = 0.9, range(min = 0.0, max = 1.0))]
+    pub energy_share_hostile_rand_max: f32,
+    #[tuning(path = "energy_share.genome_energy_share_range.genome_energy_share_min", default = 0.25, range(min = 0.0, max = 1.0))]
+    pub genome_energy_share_min: f32,
+    #[tuning(path = "energy_share.genome_energy_share_range.genome_energy_share_max", default = 0.75, range(min = 0.0, max = 1.0))]
+    pub genome_energy_share_max: f32,
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiBite {
+    #[tuning(path = "bite.bite_enabled", default = true)]
     pub bite_enabled: bool,
+    /// Multiplier on each genome's bite size
+    #[tuning(path = "bite.bite_size_scale", default = 1.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
     pub bite_size_scale: f32,
-    pub genome_bite_size_range: ApiGenomeBiteSizeRange,
-}
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeBiteSizeRange { pub genome_bite_size_min: u32, pub genome_bite_size_max: u32 }
+    #[tuning(path = "bite.genome_bite_size_range.genome_bite_size_min", default = 0)]
+    pub genome_bite_size_min: u32,
+    #[tuning(path = "bite.genome_bite_size_range.genome_bite_size_max", default = 400)]
+    pub genome_bite_size_max: u32,
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiMaxAge {
-    pub genome_max_age_range: ApiGenomeMaxAgeRange,
+    #[tuning(path = "max_age.genome_max_age_range.genome_max_age_min", default = 90)]
+    pub genome_max_age_min: u32,
+    #[tuning(path = "max_age.genome_max_age_range.genome_max_age_max", default = 120)]
+    pub genome_max_age_max: u32,
+    /// Life points lost per life tick once a ball is past its max age
+    #[tuning(path = "max_age.survival_cost_per_tick", default = 1)]
     pub survival_cost_per_tick: u32,
+
+    #[tuning(path = "reproduction.genome_reproduction_rate_range.genome_reproduction_rate_min", default = 0.00625 * 1.9, range(min = 0.0, max = 1.0))]
+    pub genome_reproduction_rate_min: f32,
+    #[tuning(path = "reproduction.genome_reproduction_rate_range.genome_reproduction_rate_max", default = 0.00625 * 2.0, range(min = 0.0, max = 1.0))]
+    pub genome_reproduction_rate_max: f32,
+    #[tuning(path = "reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_min", default = 0)]
+    pub genome_safe_reproduction_points_min: u32,
+    #[tuning(path = "reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_max", default = 1000)]
+    pub genome_safe_reproduction_points_max: u32,
+
+    /// Life points a nutrient peg feeds each touching ball per peg tick (0.5 s)
+    #[tuning(path = "pegs.peg_nutrient_per_tick", default = 20)]
+    pub peg_nutrient_per_tick: u32,
+    /// Life points a draining peg takes from each touching ball per peg tick (0.5 s)
+    #[tuning(path = "pegs.peg_drain_per_tick", default = 20)]
+    pub peg_drain_per_tick: u32,
+    /// Seconds a sticky peg holds a ball (and the cooldown before it can grab it again)
+    #[tuning(path = "pegs.peg_sticky_hold_secs", default = 5.0, range(min = 0.0, max = "MAX_TUNING_VALUE"))]
+    pub peg_sticky_hold_secs: f32,
+
+    #[tuning(path = "labels.collision.show_collision_labels", default = false)]
+    pub show_collision_labels: bool,
+    #[tuning(path = "labels.collision.collision_label_force_min", default = 2.0)]
+    pub collision_label_force_min: f32, // display units (force / PPM)
+    #[tuning(path = "labels.break.show_break_labels", default = false)]
+    pub show_break_labels: bool,
+    #[tuning(path = "labels.break.break_label_impulse_min", default = 20.0)]
+    pub break_label_impulse_min: f32,   // raw impulse units
+    #[tuning(path = "labels.age.show_age_labels", default = false)]
+    pub show_age_labels: bool,
+    #[tuning(path = "labels.age.age_label_range.age_label_min", default = 0.0)]
+    pub age_label_min: f32,
+    #[tuning(path = "labels.age.age_label_range.age_label_max", default = f32::MAX)]
+    pub age_label_max: f32,
+    #[tuning(path = "labels.energy.show_energy_labels", default = false)]
+    pub show_energy_labels: bool,
+    #[tuning(path = "labels.energy.energy_label_range.energy_label_min", default = 0.0)]
+    pub energy_label_min: f32,
+    #[tuning(path = "labels.energy.energy_label_range.energy_label_max", default = f32::MAX)]
+    pub energy_label_max: f32,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeMaxAgeRange { pub genome_max_age_min: u32, pub genome_max_age_max: u32 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiReproduction {
-    pub genome_reproduction_rate_range: ApiGenomeReproductionRateRange,
-    pub genome_safe_reproduction_points_range: ApiGenomeSafeReproductionPointsRange,
+/// Body of PATCH /tuning: a partial update plus an optional reason for the tuning history.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TuningPatch {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    #[serde(flatten)]
+    pub update: ApiTuningUpdate,
 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeReproductionRateRange { pub genome_reproduction_rate_min: f32, pub genome_reproduction_rate_max: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiGenomeSafeReproductionPointsRange { pub genome_safe_reproduction_points_min: u32, pub genome_safe_reproduction_points_max: u32 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiLabels {
-    pub collision: ApiCollisionLabels,
-    #[serde(rename = "break")] pub break_labels: ApiBreakLabels,
-    pub age: ApiAgeLabels,
-    pub energy: ApiEnergyLabels,
+/// Largest magnitude accepted for physics and genome values. Anything near f32::MAX overflows
+/// range arithmetic in `rng.gen_range`; label thresholds are exempt since f32::MAX means "no limit".
+pub const MAX_TUNING_VALUE: f32 = 1.0e9;
+
+/// One rejected tuning value, addressed by its JSON path in `ApiTuning`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TuningViolation {
+    pub field: String,
+    pub reason: String,
 }
+
+/// Body of a 422 response from PATCH /tuning.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiCollisionLabels { pub show_collision_labels: bool, pub collision_label_force_min: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiBreakLabels { pub show_break_labels: bool, pub break_label_impulse_min: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiAgeLabels { pub show_age_labels: bool, pub age_label_range: ApiAgeLabelRange }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiAgeLabelRange { pub age_label_min: f32, pub age_label_max: f32 }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiEnergyLabels { pub show_energy_labels: bool, pub energy_label_range: ApiEnergyLabelRange }
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ApiEnergyLabelRange { pub energy_label_min: f32, pub energy_label_max: f32 }
-
-// Partial update types mirror ApiTuning with Options down to lowest level
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiTuningUpdate {
-    pub stickiness: Option<ApiStickinessUpdate>,
-    pub energy_share: Option<ApiEnergyShareUpdate>,
-    pub bite: Option<ApiBiteUpdate>,
-    pub max_age: Option<ApiMaxAgeUpdate>,
-    pub reproduction: Option<ApiReproductionUpdate>,
-    pub labels: Option<ApiLabelsUpdate>,
+pub struct TuningValidationError {
+    pub error: String,
+    pub violations: Vec<TuningViolation>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiStickinessUpdate { pub stick_range: Option<ApiStickRangeUpdate>, pub break_threshold: Option<f32>, pub contact_force_performance_cutoff_fudge_factor: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiStickRangeUpdate { pub rel_vel_min: Option<f32>, pub rel_vel_max: Option<f32> }
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiEnergyShareUpdate {
-    pub energy_transfer_enabled: Option<bool>,
-    pub energy_share_diff_threshold: Option<u32>,
-    pub genome_friendly_scent_range: Option<f32>,
-    pub genome_friendly_distance_range: Option<ApiGenomeFriendlyDistanceRangeUpdate>,
-    pub energy_share_friendly_rate: Option<f32>,
-    pub energy_share_parent_not_friendly_child_friendly_rate: Option<f32>,
-    pub energy_share_parent_friendly_child_not_friendly_rate: Option<f32>,
-    pub energy_share_hostile_rand_range: Option<ApiEnergyShareHostileRandRangeUpdate>,
-    pub genome_energy_share_range: Option<ApiGenomeEnergyShareRangeUpdate>,
-}
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeFriendlyDistanceRangeUpdate { pub genome_friendly_distance_min: Option<f32>, pub genome_friendly_distance_max: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiEnergyShareHostileRandRangeUpdate { pub energy_share_hostile_rand_min: Option<f32>, pub energy_share_hostile_rand_max: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeEnergyShareRangeUpdate { pub genome_energy_share_min: Option<f32>, pub genome_energy_share_max: Option<f32> }
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiBiteUpdate { pub bite_enabled: Option<bool>, pub bite_size_scale: Option<f32>, pub genome_bite_size_range: Option<ApiGenomeBiteSizeRangeUpdate> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeBiteSizeRangeUpdate { pub genome_bite_size_min: Option<u32>, pub genome_bite_size_max: Option<u32> }
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiMaxAgeUpdate { pub genome_max_age_range: Option<ApiGenomeMaxAgeRangeUpdate>, pub survival_cost_per_tick: Option<u32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeMaxAgeRangeUpdate { pub genome_max_age_min: Option<u32>, pub genome_max_age_max: Option<u32> }
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiReproductionUpdate {
-    pub genome_reproduction_rate_range: Option<ApiGenomeReproductionRateRangeUpdate>,
-    pub genome_safe_reproduction_points_range: Option<ApiGenomeSafeReproductionPointsRangeUpdate>,
-}
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeReproductionRateRangeUpdate { pub genome_reproduction_rate_min: Option<f32>, pub genome_reproduction_rate_max: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiGenomeSafeReproductionPointsRangeUpdate { pub genome_safe_reproduction_points_min: Option<u32>, pub genome_safe_reproduction_points_max: Option<u32> }
-
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiLabelsUpdate {
-    pub collision: Option<ApiCollisionLabelsUpdate>,
-    #[serde(rename = "break")] pub break_labels: Option<ApiBreakLabelsUpdate>,
-    pub age: Option<ApiAgeLabelsUpdate>,
-    pub energy: Option<ApiEnergyLabelsUpdate>,
+impl TuningValidationError {
+    pub fn new(violations: Vec<TuningViolation>) -> Self {
+        Self { error: "invalid tuning".to_string(), violations }
+    }
 }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiCollisionLabelsUpdate { pub show_collision_labels: Option<bool>, pub collision_label_force_min: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiBreakLabelsUpdate { pub show_break_labels: Option<bool>, pub break_label_impulse_min: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiAgeLabelsUpdate { pub show_age_labels: Option<bool>, pub age_label_range: Option<ApiAgeLabelRangeUpdate> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiAgeLabelRangeUpdate { pub age_label_min: Option<f32>, pub age_label_max: Option<f32> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiEnergyLabelsUpdate { pub show_energy_labels: Option<bool>, pub energy_label_range: Option<ApiEnergyLabelRangeUpdate> }
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct ApiEnergyLabelRangeUpdate { pub energy_label_min: Option<f32>, pub energy_label_max: Option<f32> }
-
-impl ApiTuningUpdate {
-    pub fn apply_to(self, t: &mut PhysicsTuning) {
-        if let Some(st) = self.stickiness {
-            if let Some(sr) = st.stick_range {
-                if let Some(v) = sr.rel_vel_min { t.rel_vel_min = v; }
-                if let Some(v) = sr.rel_vel_max { t.rel_vel_max = v; }
-            }
-            if let Some(v) = st.break_threshold { t.break_force_threshold = v; }
-            if let Some(v) = st.contact_force_performance_cutoff_fudge_factor { t.contact_force_performance_cutoff_fudge_factor = v; }
-        }
-        if let Some(es) = self.energy_share {
-            if let Some(v) = es.energy_transfer_enabled { t.energy_transfer_enabled = v; }
-            if let Some(v) = es.energy_share_diff_threshold { t.energy_share_diff_threshold = v; }
-            if let Some(v) = es.genome_friendly_scent_range { t.genome_friendly_scent_range = v; }
-            if let Some(gfdr) = es.genome_friendly_distance_range {
-                if let Some(v) = gfdr.genome_friendly_distance_min { t.genome_friendly_distance_min = v; }
-                if let Some(v) = gfdr.genome_friendly_distance_max { t.genome_friendly_distance_max = v; }
-            }
-            if let Some(v) = es.energy_share_friendly_rate { t.energy_share_friendly_rate = v; }
-            if let Some(v) = es.energy_share_parent_not_friendly_child_friendly_rate { t.energy_share_parent_not_friendly_child_friendly_rate = v; }
-            if let Some(v) = es.energy_share_parent_friendly_child_not_friendly_rate { t.energy_share_parent_friendly_child_not_friendly_rate = v; }
-            if let Some(hrr) = es.energy_share_hostile_rand_range {
-                if let Some(v) = hrr.energy_share_hostile_rand_min { t.energy_share_hostile_rand_min = v; }
-                if let Some(v) = hrr.energy_share_hostile_rand_max { t.energy_share_hostile_rand_max = v; }
-            }
-            if let Some(ger) = es.genome_energy_share_range {
-                if let Some(v) = ger.genome_energy_share_min { t.genome_energy_share_min = v; }
-                if let Some(v) = ger.genome_energy_share_max { t.genome_energy_share_max = v; }
-            }
-        }
-        if let Some(b) = self.bite {
-            if let Some(v) = b.bite_enabled { t.bite_enabled = v; }
-            if let Some(v) = b.bite_size_scale { t.bite_size_scale = v; }
-            if let Some(gbr) = b.genome_bite_size_range {
-                if let Some(v) = gbr.genome_bite_size_min { t.genome_bite_size_min = v; }
-                if let Some(v) = gbr.genome_bite_size_max { t.genome_bite_size_max = v; }
-            }
+
+// Accumulates violations so a single response lists every bad field.
+#[derive(Default)]
+struct TuningChecks(Vec<TuningViolation>);
+
+impl TuningChecks {
+    fn fail(&mut self, field: &str, reason: String) {
+        self.0.push(TuningViolation { field: field.to_string(), reason });
+    }
+
+    fn finite(&mut self, field: &str, v: f32) -> bool {
+        if v.is_finite() {
+            true
+        } else {
+            self.fail(field, format!("must be a finite number, got {v}"));
+            false
         }
-        if let Some(ma) = self.max_age {
-            if let Some(gmr) = ma.genome_max_age_range {
-                if let Some(v) = gmr.genome_max_age_min { t.genome_max_age_min = v; }
-                if let Some(v) = gmr.genome_max_age_max { t.genome_max_age_max = v; }
-            }
-            if let Some(v) = ma.survival_cost_per_tick { t.survival_cost_per_tick = v; }
+    }
+
+    // Finite, within [lo, hi]
+    fn within(&mut self, field: &str, v: f32, lo: f32, hi: f32) {
+        if self.finite(field, v) && !(lo..=hi).contains(&v) {
+            self.fail(field, format!("must be between {lo} and {hi}, got {v}"));
         }
-        if let Some(r) = self.reproduction {
-            if let Some(rr) = r.genome_reproduction_rate_range {
-                if let Some(v) = rr.genome_reproduction_rate_min { t.genome_reproduction_rate_min = v; }
-                if let Some(v) = rr.genome_reproduction_rate_max { t.genome_reproduction_rate_max = v; }
-            }
-            if let Some(sr) = r.genome_safe_reproduction_points_range {
-                if let Some(v) = sr.genome_safe_reproduction_points_min { t.genome_safe_reproduction_points_min = v; }
-                if let Some(v) = sr.genome_safe_reproduction_points_max { t.genome_safe_reproduction_points_max = v; }
-            }
+    }
+
+    // Bounds handed to rng.gen_range, which panics unless min < max
+    fn gen_range_f32(&mut self, min_field: &str, min: f32, max_field: &str, max: f32) {
+        let min_ok = self.finite(min_field, min);
+        let max_ok = self.finite(max_field, max);
+        if min_ok && max_ok && min >= max {
+            self.fail(min_field, format!("must be less than {max_field} ({max}), got {min}"));
         }
-        if let Some(l) = self.labels {
-            if let Some(c) = l.collision {
-                if let Some(v) = c.show_collision_labels { t.show_collision_labels = v; }
-                if let Some(v) = c.collision_label_force_min { t.collision_label_force_min = v; }
-            }
-            if let Some(b) = l.break_labels {
-                if let Some(v) = b.show_break_labels { t.show_break_labels = v; }
-                if let Some(v) = b.break_label_impulse_min { t.break_label_impulse_min = v; }
-            }
-            if let Some(a) = l.age {
-                if let Some(v) = a.show_age_labels { t.show_age_labels = v; }
-                if let Some(ar) = a.age_label_range {
-                    if let Some(v) = ar.age_label_min { t.age_label_min = v; }
-                    if let Some(v) = ar.age_label_max { t.age_label_max = v; }
-                }
-            }
-            if let Some(e) = l.energy {
-                if let Some(v) = e.show_energy_labels { t.show_energy_labels = v; }
-                if let Some(er) = e.energy_label_range {
-                    if let Some(v) = er.energy_label_min { t.energy_label_min = v; }
-                    if let Some(v) = er.energy_label_max { t.energy_label_max = v; }
-                }
-            }
+    }
+
+    fn gen_range_u32(&mut self, min_field: &str, min: u32, max_field: &str, max: u32) {
+        if min >= max {
+            self.fail(min_field, format!("must be less than {max_field} ({max}), got {min}"));
         }
     }
-}
 
-impl From<&PhysicsTuning> for ApiTuning {
-    fn from(t: &PhysicsTuning) -> Self {
-        ApiTuning {
-            stickiness: ApiStickiness {
-                stick_range: ApiStickRange { rel_vel_min: t.rel_vel_min, rel_vel_max: t.rel_vel_max },
-                break_threshold: t.break_force_threshold,
-                contact_force_performance_cutoff_fudge_factor: t.contact_force_performance_cutoff_fudge_factor,
-            },
-            energy_share: ApiEnergyShare {
-                energy_transfer_enabled: t.energy_transfer_enabled,
-                energy_share_diff_threshold: t.energy_share_diff_threshold,
-                genome_friendly_scent_range: t.genome_friendly_scent_range,
-                genome_friendly_distance_range: ApiGenomeFriendlyDistanceRange { genome_friendly_distance_min: t.genome_friendly_distance_min, genome_friendly_distance_max: t.genome_friendly_distance_max },
-                energy_share_friendly_rate: t.energy_share_friendly_rate,
-                energy_share_parent_not_friendly_child_friendly_rate: t.energy_share_parent_not_friendly_child_friendly_rate,
-                energy_share_parent_friendly_child_not_friendly_rate: t.energy_share_parent_friendly_child_not_friendly_rate,
-                energy_share_hostile_rand_range: ApiEnergyShareHostileRandRange { energy_share_hostile_rand_min: t.energy_share_hostile_rand_min, energy_share_hostile_rand_max: t.energy_share_hostile_rand_max },
-                genome_energy_share_range: ApiGenomeEnergyShareRange { genome_energy_share_min: t.genome_energy_share_min, genome_energy_share_max: t.genome_energy_share_max },
-            },
-            bite: ApiBite { bite_enabled: t.bite_enabled, bite_size_scale: t.bite_size_scale, genome_bite_size_range: ApiGenomeBiteSizeRange { genome_bite_size_min: t.genome_bite_size_min, genome_bite_size_max: t.genome_bite_size_max } },
-            max_age: ApiMaxAge { genome_max_age_range: ApiGenomeMaxAgeRange { genome_max_age_min: t.genome_max_age_min, genome_max_age_max: t.genome_max_age_max }, survival_cost_per_tick: t.survival_cost_per_tick },
-            reproduction: ApiReproduction {
-                genome_reproduction_rate_range: ApiGenomeReproductionRateRange { genome_reproduction_rate_min: t.genome_reproduction_rate_min, genome_reproduction_rate_max: t.genome_reproduction_rate_max },
-                genome_safe_reproduction_points_range: ApiGenomeSafeReproductionPointsRange { genome_safe_reproduction_points_min: t.genome_safe_reproduction_points_min, genome_safe_reproduction_points_max: t.genome_safe_reproduction_points_max },
-            },
-            labels: ApiLabels {
-                collision: ApiCollisionLabels { show_collision_labels: t.show_collision_labels, collision_label_force_min: t.collision_label_force_min },
-                break_labels: ApiBreakLabels { show_break_labels: t.show_break_labels, break_label_impulse_min: t.break_label_impulse_min },
-                age: ApiAgeLabels { show_age_labels: t.show_age_labels, age_label_range: ApiAgeLabelRange { age_label_min: t.age_label_min, age_label_max: t.age_label_max } },
-                energy: ApiEnergyLabels { show_energy_labels: t.show_energy_labels, energy_label_range: ApiEnergyLabelRange { energy_label_min: t.energy_label_min, energy_label_max: t.energy_label_max } },
-            },
+    // Display/comparison bounds; equal is fine
+    fn ordered(&mut self, min_field: &str, min: f32, max_field: &str, max: f32) {
+        if !min.is_nan() && !max.is_nan() && min > max {
+            self.fail(min_field, format!("must not exceed {max_field} ({max}), got {min}"));
         }
     }
-}
 
-impl From<ApiTuning> for PhysicsTuning {
-    fn from(api: ApiTuning) -> Self {
-        PhysicsTuning {
-            rel_vel_min: api.stickiness.stick_range.rel_vel_min,
-            rel_vel_max: api.stickiness.stick_range.rel_vel_max,
-            break_force_threshold: api.stickiness.break_threshold,
-            contact_force_performance_cutoff_fudge_factor: api.stickiness.contact_force_performance_cutoff_fudge_factor,
-            energy_transfer_enabled: api.energy_share.energy_transfer_enabled,
-            energy_share_diff_threshold: api.energy_share.energy_share_diff_threshold,
-            energy_share_friendly_rate: api.energy_share.energy_share_friendly_rate,
-            energy_share_parent_not_friendly_child_friendly_rate: api.energy_share.energy_share_parent_not_friendly_child_friendly_rate,
-            energy_share_parent_friendly_child_not_friendly_rate: api.energy_share.energy_share_parent_friendly_child_not_friendly_rate,
-            energy_share_hostile_rand_min: api.energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_min,
-            energy_share_hostile_rand_max: api.energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_max,
-            bite_enabled: api.bite.bite_enabled,
-            bite_size_scale: api.bite.bite_size_scale,
-            genome_bite_size_min: api.bite.genome_bite_size_range.genome_bite_size_min,
-            genome_bite_size_max: api.bite.genome_bite_size_range.genome_bite_size_max,
-            genome_energy_share_min: api.energy_share.genome_energy_share_range.genome_energy_share_min,
-            genome_energy_share_max: api.energy_share.genome_energy_share_range.genome_energy_share_max,
-            genome_friendly_distance_min: api.energy_share.genome_friendly_distance_range.genome_friendly_distance_min,
-            genome_friendly_distance_max: api.energy_share.genome_friendly_distance_range.genome_friendly_distance_max,
-            genome_friendly_scent_range: api.energy_share.genome_friendly_scent_range,
-            genome_max_age_min: api.max_age.genome_max_age_range.genome_max_age_min,
-            genome_max_age_max: api.max_age.genome_max_age_range.genome_max_age_max,
-            genome_reproduction_rate_min: api.reproduction.genome_reproduction_rate_range.genome_reproduction_rate_min,
-            genome_reproduction_rate_max: api.reproduction.genome_reproduction_rate_range.genome_reproduction_rate_max,
-            genome_safe_reproduction_points_min: api.reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_min,
-            genome_safe_reproduction_points_max: api.reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_max,
-            survival_cost_per_tick: api.max_age.survival_cost_per_tick,
-            show_collision_labels: api.labels.collision.show_collision_labels,
-            collision_label_force_min: api.labels.collision.collision_label_force_min,
-            show_break_labels: api.labels.break_labels.show_break_labels,
-            break_label_impulse_min: api.labels.break_labels.break_label_impulse_min,
-            show_age_labels: api.labels.age.show_age_labels,
-            age_label_min: api.labels.age.age_label_range.age_label_min,
-            age_label_max: api.labels.age.age_label_range.age_label_max,
-            show_energy_labels: api.labels.energy.show_energy_labels,
-            energy_label_min: api.labels.energy.energy_label_range.energy_label_min,
-            energy_label_max: api.labels.energy.energy_label_range.energy_label_max,
+    fn not_nan(&mut self, field: &str, v: f32) {
+        if v.is_nan() {
+            self.fail(field, "must be a number, got NaN".to_string());
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default, Resource)]
-pub struct PhysicsTuning {
-    pub rel_vel_min: f32,
-    pub rel_vel_max: f32,
-    // Breaking threshold (raw Rapier impulse units for joints)
-    pub break_force_threshold: f32,
-    // Performance cutoff fudge factor for mapping rel_vel_min -> contact force threshold
-    // Effective multiplier used = (1.0 - contact_force_performance_cutoff_fudge_factor)
-    pub contact_force_performance_cutoff_fudge_factor: f32,
-    // Energy transfer and bite behavior between stuck pairs
-    pub energy_transfer_enabled: bool,
-    pub energy_share_diff_threshold: u32,
-    pub energy_share_friendly_rate: f32,
-    pub energy_share_parent_not_friendly_child_friendly_rate: f32,
-    pub energy_share_parent_friendly_child_not_friendly_rate: f32,
-    pub energy_share_hostile_rand_min: f32,
-    pub energy_share_hostile_rand_max: f32,
-    pub bite_enabled: bool,
-    pub bite_size_scale: f32,
-    // Genome generation ranges for new balls
-    pub genome_bite_size_min: u32,
-    pub genome_bite_size_max: u32,
-    pub genome_energy_share_min: f32,
-    pub genome_energy_share_max: f32,
-    pub genome_friendly_distance_min: f32,
-    pub genome_friendly_distance_max: f32,
-    pub genome_friendly_scent_range: f32,
-    pub genome_max_age_min: u32,
-    pub genome_max_age_max: u32,
-    pub genome_reproduction_rate_min: f32,
-    pub genome_reproduction_rate_max: f32,
-    pub genome_safe_reproduction_points_min: u32,
-    pub genome_safe_reproduction_points_max: u32,
-    // Aging/decay
-    pub survival_cost_per_tick: u32,
-    // Label visibility/thresholds
-    pub show_collision_labels: bool,
-    pub collision_label_force_min: f32, // display units (force / PPM)
-    pub show_break_labels: bool,
-    pub break_label_impulse_min: f32,   // raw impulse units
-    // New: Age/Energy labels
-    pub show_age_labels: bool,
-    pub age_label_min: f32,
-    pub age_label_max: f32,
-    pub show_energy_labels: bool,
-    pub energy_label_min: f32,
-    pub energy_label_max: f32,
+impl PhysicsTuning {
+    /// Check every value the simulation relies on: finite numbers, rates in [0, 1], and genome
+    /// ranges that `rng.gen_range` and `clamp` can use. Returns all violations, not just the first.
+    pub fn validate(&self) -> Result<(), Vec<TuningViolation>> {
+        let mut c = TuningChecks::default();
+        self.check_ranges(|field, v, lo, hi| c.within(field, v, lo, hi));
+        c.ordered("stickiness.stick_range.rel_vel_min", self.rel_vel_min, "stickiness.stick_range.rel_vel_max", self.rel_vel_max);
+
+        c.gen_range_f32(
+            "energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_min", self.energy_share_hostile_rand_min,
+            "energy_share.energy_share_hostile_rand_range.energy_share_hostile_rand_max", self.energy_share_hostile_rand_max,
+        );
+        c.gen_range_f32(
+            "energy_share.genome_energy_share_range.genome_energy_share_min", self.genome_energy_share_min,
+            "energy_share.genome_energy_share_range.genome_energy_share_max", self.genome_energy_share_max,
+        );
+        c.gen_range_f32(
+            "energy_share.genome_friendly_distance_range.genome_friendly_distance_min", self.genome_friendly_distance_min,
+            "energy_share.genome_friendly_distance_range.genome_friendly_distance_max", self.genome_friendly_distance_max,
+        );
+
+        c.gen_range_u32(
+            "bite.genome_bite_size_range.genome_bite_size_min", self.genome_bite_size_min,
+            "bite.genome_bite_size_range.genome_bite_size_max", self.genome_bite_size_max,
+        );
+
+        c.gen_range_u32(
+            "max_age.genome_max_age_range.genome_max_age_min", self.genome_max_age_min,
+            "max_age.genome_max_age_range.genome_max_age_max", self.genome_max_age_max,
+        );
+
+        c.gen_range_f32(
+            "reproduction.genome_reproduction_rate_range.genome_reproduction_rate_min", self.genome_reproduction_rate_min,
+            "reproduction.genome_reproduction_rate_range.genome_reproduction_rate_max", self.genome_reproduction_rate_max,
+        );
+        c.gen_range_u32(
+            "reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_min", self.genome_safe_reproduction_points_min,
+            "reproduction.genome_safe_reproduction_points_range.genome_safe_reproduction_points_max", self.genome_safe_reproduction_points_max,
+        );
+
+        c.not_nan("labels.collision.collision_label_force_min", self.collision_label_force_min);
+        c.not_nan("labels.break.break_label_impulse_min", self.break_label_impulse_min);
+        c.not_nan("labels.age.age_label_range.age_label_min", self.age_label_min);
+        c.not_nan("labels.age.age_label_range.age_label_max", self.age_label_max);
+        c.ordered("labels.age.age_label_range.age_label_min", self.age_label_min, "labels.age.age_label_range.age_label_max", self.age_label_max);
+        c.not_nan("labels.energy.energy_label_range.energy_label_min", self.energy_label_min);
+        c.not_nan("labels.energy.energy_label_range.energy_label_max", self.energy_label_max);
+        c.ordered("labels.energy.energy_label_range.energy_label_min", self.energy_label_min, "labels.energy.energy_label_range.energy_label_max", self.energy_label_max);
+
+        if c.0.is_empty() { Ok(()) } else { Err(c.0) }
+    }
 }
 
 
-#[derive(Clone)]
-struct AppState {
+/// Shared state for the HTTP server: the tuning channel and mirror, plus the other world APIs.
+// Also a Resource, so world systems (e.g. the tuning schedule) change tuning the same way the server does.
+#[derive(Clone, Resource)]
+pub struct AppState {
     tx: mpsc::Sender<PhysicsTuning>,
     mirror: Arc<Mutex<PhysicsTuning>>, // for GET /tuning
+    history: Arc<Mutex<TuningHistory>>,
+    schedule: Arc<Mutex<TuningSchedule>>,
+    clock: SimClock,
+    feed: LiveFeed,
+    events: EventFeed,
+    metrics: Metrics,
+    pegs: PegsApi,
+    balls: BallsApi,
+    query: QueryApi,
+    sim: SimApi,
+    presets: PresetStore,
+}
+
+impl AppState {
+    pub fn new(tx: mpsc::Sender<PhysicsTuning>, mirror: Arc<Mutex<PhysicsTuning>>) -> Self {
+        // World-backed routes talk to empty, disconnected channels until a `with_*` builder attaches the world's own
+        let (pegs, _, _) = PegsApi::channel();
+        let (balls, ..) = BallsApi::channel();
+        let (query, _) = QueryApi::channel();
+        let (sim, _, _) = SimApi::channel();
+        Self {
+            tx,
+            mirror,
+            history: Arc::new(Mutex::new(TuningHistory::default())),
+            schedule: Arc::new(Mutex::new(TuningSchedule::default())),
+            clock: SimClock::default(),
+            feed: LiveFeed::default(),
+            events: EventFeed::default(),
+            metrics: Metrics::default(),
+            pegs,
+            balls,
+            query,
+            sim,
+            presets: PresetStore::from_env(),
+        }
+    }
+
+    pub fn with_pegs(mut self, pegs: PegsApi) -> Self {
+        self.pegs = pegs;
+        self
+    }
+
+    pub fn with_balls(mut self, balls: BallsApi) -> Self {
+        self.balls = balls;
+        self
+    }
+
+    pub fn with_query(mut self, query: QueryApi) -> Self {
+        self.query = query;
+        self
+    }
+
+    pub fn with_sim(mut self, sim: SimApi) -> Self {
+        self.sim = sim;
+        self
+    }
+
+    pub fn with_presets(mut self, presets: PresetStore) -> Self {
+        self.presets = presets;
+        self
+    }
+
+    pub fn with_history(mut self, history: TuningHistory) -> Self {
+        self.history = Arc::new(Mutex::new(history));
+        self
+    }
+
+    /// Clock the world publishes into; used to stamp history entries.
+    pub fn with_clock(mut self, clock: SimClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Feed the world publishes stats into; tuning changes are published here too.
+    pub fn with_feed(mut self, feed: LiveFeed) -> Self {
+        self.feed = feed;
+        self
+    }
+
+    pub fn feed(&self) -> &LiveFeed {
+        &self.feed
+    }
+
+    /// Feed the world publishes lifecycle events into, for GET /events/stream.
+    pub fn with_events(mut self, events: EventFeed) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn events(&self) -> &EventFeed {
+        &self.events
+    }
+
+    /// Metrics the world and the capture pipeline publish into, for GET /metrics.
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn presets(&self) -> &PresetStore {
+        &self.presets
+    }
+
+    pub fn schedule(&self) -> &Arc<Mutex<TuningSchedule>> {
+        &self.schedule
+    }
+
+    pub fn history(&self) -> &Arc<Mutex<TuningHistory>> {
+        &self.history
+    }
+
+    pub fn sim(&self) -> &SimApi {
+        &self.sim
+    }
+
+    pub fn current_tuning(&self) -> PhysicsTuning {
+        self.mirror.lock().unwrap().clone()
+    }
+
+    fn stamp(&self) -> ChangeStamp {
+        ChangeStamp { frame: self.clock.frame(), sim_seconds: self.clock.seconds() }
+    }
+
+    // Validate, commit to the mirror and send to Bevy. Caller holds the mirror lock.
+    fn commit(&self, guard: &mut PhysicsTuning, candidate: PhysicsTuning) -> Result<PhysicsTuning, TuningValidationError> {
+        candidate.validate().map_err(TuningValidationError::new)?;
+        *guard = candidate.clone();
+        // Bevy applies it authoritatively on its next update
+        let _ = self.tx.send(candidate.clone());
+        self.feed.publish(LiveMessage::Tuning { tuning: ApiTuning::from(&candidate) });
+        Ok(candidate)
+    }
+
+    /// Edit a copy of the live tuning; if it validates, commit it, log it to the history and send it to Bevy.
+    pub fn update_tuning(
+        &self,
+        source: &str,
+        note: Option<String>,
+        edit: impl FnOnce(&mut PhysicsTuning),
+    ) -> Result<PhysicsTuning, TuningValidationError> {
+        let mut guard = self.mirror.lock().unwrap();
+        let before = guard.clone();
+        let mut candidate = before.clone();
+        edit(&mut candidate);
+        let after = self.commit(&mut guard, candidate)?;
+        self.history.lock().unwrap().record(&before, &after, source, note, self.stamp());
+        Ok(after)
+    }
+
+    /// Like `update_tuning` but without a history entry; for intermediate ramp steps.
+    pub fn update_tuning_unlogged(&self, edit: impl FnOnce(&mut PhysicsTuning)) -> Result<PhysicsTuning, TuningValidationError> {
+        let mut guard = self.mirror.lock().unwrap();
+        let mut candidate = guard.clone();
+        edit(&mut candidate);
+        self.commit(&mut guard, candidate)
+    }
+
+    /// Add a history entry for a change already committed (e.g. a whole ramp, start to finish).
+    pub fn log_change(&self, before: &PhysicsTuning, after: &PhysicsTuning, source: &str, note: Option<String>) {
+        self.history.lock().unwrap().record(before, after, source, note, self.stamp());
+    }
+
+    pub fn apply_tuning(&self, source: &str, tuning: PhysicsTuning) -> Result<PhysicsTuning, TuningValidationError> {
+        self.update_tuning(source, None, |t| *t = tuning)
+    }
+
+    /// Roll back the latest change still on the undo stack. `Ok(None)` when there is nothing to undo.
+    pub fn undo(&self, note: Option<String>) -> Result<Option<PhysicsTuning>, TuningValidationError> {
+        let mut guard = self.mirror.lock().unwrap();
+        let mut history = self.history.lock().unwrap();
+        let before = guard.clone();
+        let Some(candidate) = history.peek_undo(&before) else { return Ok(None); };
+        let after = self.commit(&mut guard, candidate)?;
+        history.commit_undo(&before, &after, note, self.stamp());
+        Ok(Some(after))
+    }
+
+    /// Re-apply the latest undone change. `Ok(None)` when there is nothing to redo.
+    pub fn redo(&self, note: Option<String>) -> Result<Option<PhysicsTuning>, TuningValidationError> {
+        let mut guard = self.mirror.lock().unwrap();
+        let mut history = self.history.lock().unwrap();
+        let before = guard.clone();
+        let Some(candidate) = history.peek_redo(&before) else { return Ok(None); };
+        let after = self.commit(&mut guard, candidate)?;
+        history.commit_redo(&before, &after, note, self.stamp());
+        Ok(Some(after))
+    }
 }
 
 async fn get_tuning(State(state): State<AppState>) -> Json<ApiTuning> {
@@ -392,30 +508,112 @@ async fn get_tuning(State(state): State<AppState>) -> Json<ApiTuning> {
     Json(ApiTuning::from(&*guard))
 }
 
+/// JSON Schema for GET/PATCH /tuning, derived from the Api structs, with the
+/// `PhysicsTuning::default()` value of every field filled in as `default`.
+pub fn tuning_schema() -> Value {
+    let mut root = serde_json::to_value(schemars::schema_for!(ApiTuning)).unwrap_or_default();
+    let defaults = serde_json::to_value(ApiTuning::from(&PhysicsTuning::default())).unwrap_or_default();
+    let mut defs = match root.as_object_mut().and_then(|o| o.remove("definitions")) {
+        Some(Value::Object(defs)) => defs,
+        _ => Map::new(),
+    };
+    fill_schema_defaults(&mut root, &defaults, &mut defs);
+    if let Some(o) = root.as_object_mut() {
+        o.insert("definitions".to_string(), Value::Object(defs));
+    }
+    root
+}
+
+// Nested structs are `$ref`s into `definitions` (wrapped in `allOf` when the field has docs).
+fn schema_ref(node: &Value) -> Option<String> {
+    let r = node.get("$ref").or_else(|| node.get("allOf")?.get(0)?.get("$ref"))?;
+    r.as_str()?.strip_prefix("#/definitions/").map(str::to_string)
+}
+
+fn fill_schema_defaults(node: &mut Value, default: &Value, defs: &mut Map<String, Value>) {
+    let Value::Object(fields) = default else {
+        if let Some(o) = node.as_object_mut() {
+            o.insert("default".to_string(), default.clone());
+        }
+        return;
+    };
+    if let Some(name) = schema_ref(node) {
+        if let Some(mut def) = defs.remove(&name) {
+            fill_schema_defaults(&mut def, default, defs);
+            defs.insert(name, def);
+        }
+        return;
+    }
+    let Some(Value::Object(props)) = node.get_mut("properties") else { return; };
+    for (field, value) in fields {
+        if let Some(prop) = props.get_mut(field) {
+            fill_schema_defaults(prop, value, defs);
+        }
+    }
+}
+
+async fn get_tuning_schema() -> Json<Value> {
+    Json(tuning_schema())
+}
+
 async fn patch_tuning(
     State(state): State<AppState>,
-    Json(api_update): Json<ApiTuningUpdate>,
-) -> Json<ApiTuning> {
-    // Apply partial update into current tuning
-    let new_tuning = {
-        let mut guard = state.mirror.lock().unwrap();
-        api_update.clone().apply_to(&mut guard);
-        guard.clone()
-    };
-    // Send to Bevy for authoritative apply
-    let _ = state.tx.send(new_tuning.clone());
-    // Return current mirror as hierarchical response
-    {
-        let guard = state.mirror.lock().unwrap();
-        Json(ApiTuning::from(&*guard))
+    Json(patch): Json<TuningPatch>,
+) -> Result<Json<ApiTuning>, (StatusCode, Json<TuningValidationError>)> {
+    // Apply partial update to a copy; the mirror only changes if the result is valid
+    let TuningPatch { note, update } = patch;
+    let new_tuning = state
+        .update_tuning("patch", note, |t| update.apply_to(t))
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, Json(e)))?;
+    // Return the new tuning as hierarchical response
+    Ok(Json(ApiTuning::from(&new_tuning)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TuningHistoryResponse {
+    pub can_undo: bool,
+    pub can_redo: bool,
+    pub changes: Vec<TuningChange>,
+}
+
+async fn get_tuning_history(State(state): State<AppState>) -> Json<TuningHistoryResponse> {
+    let history = state.history().lock().unwrap();
+    Json(TuningHistoryResponse { can_undo: history.can_undo(), can_redo: history.can_redo(), changes: history.entries().to_vec() })
+}
+
+/// Optional body for undo/redo.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryStepRequest {
+    pub note: Option<String>,
+}
+
+type HistoryStepResult = Result<Json<ApiTuning>, (StatusCode, Json<TuningValidationError>)>;
+
+fn history_step(result: Result<Option<PhysicsTuning>, TuningValidationError>, empty: &str) -> HistoryStepResult {
+    match result {
+        Ok(Some(t)) => Ok(Json(ApiTuning::from(&t))),
+        Ok(None) => Err((StatusCode::CONFLICT, Json(TuningValidationError { error: empty.to_string(), violations: Vec::new() }))),
+        Err(e) => Err((StatusCode::UNPROCESSABLE_ENTITY, Json(e))),
     }
 }
 
-pub fn spawn_axum_server(
-    addr: SocketAddr,
-    tx: mpsc::Sender<PhysicsTuning>,
-    mirror: Arc<Mutex<PhysicsTuning>>,
-) {
+async fn post_tuning_undo(State(state): State<AppState>, body: Option<Json<HistoryStepRequest>>) -> HistoryStepResult {
+    let note = body.and_then(|Json(b)| b.note);
+    history_step(state.undo(note), "nothing to undo")
+}
+
+async fn post_tuning_redo(State(state): State<AppState>, body: Option<Json<HistoryStepRequest>>) -> HistoryStepResult {
+    let note = body.and_then(|Json(b)| b.note);
+    history_step(state.redo(note), "nothing to redo")
+}
+
+/// Bind the tuning server and serve it on its own thread. Binding happens here, so a taken
+/// port is reported to the caller instead of panicking the server thread.
+pub fn spawn_axum_server(http: &HttpConfig, state: AppState) -> std::io::Result<SocketAddr> {
+    let listener = std::net::TcpListener::bind(http.addr)?;
+    listener.set_nonblocking(true)?;
+    let addr = listener.local_addr()?;
+    let app = build_router_with_http(state, http);
     std::thread::spawn(move || {
         let rt = Builder::new_current_thread()
             .enable_all()
@@ -423,26 +621,54 @@ pub fn spawn_axum_server(
             .expect("tokio runtime");
 
         rt.block_on(async move {
-            let app = build_router(tx, mirror);
-
-            let listener = tokio::net::TcpListener::bind(addr).await.expect("bind http");
+            let listener = match tokio::net::TcpListener::from_std(listener) {
+                Ok(l) => l,
+                Err(e) => return eprintln!("[error] tuning server: {e}"),
+            };
             eprintln!("[diag] tuning server on http://{}", addr);
-            axum::serve(listener, app).await.expect("serve http");
+            if let Err(e) = axum::serve(listener, app).await {
+                eprintln!("[error] tuning server: {e}");
+            }
         });
     });
+    Ok(addr)
 }
 
-fn build_router(tx: mpsc::Sender<PhysicsTuning>, mirror: Arc<Mutex<PhysicsTuning>>) -> Router {
-    let state = AppState { tx, mirror };
+/// Every route, over `state`.
+pub fn build_router_with_state(state: AppState) -> Router {
+    let pegs = state.pegs.clone();
+    let balls = state.balls.clone();
+    let query = state.query.clone();
+    let sim = state.sim.clone();
     Router::new()
         .route("/tuning", get(get_tuning).patch(patch_tuning))
+        .route("/tuning/schema", get(get_tuning_schema))
+        .route("/tuning/history", get(get_tuning_history))
+        .route("/tuning/undo", post(post_tuning_undo))
+        .route("/tuning/redo", post(post_tuning_redo))
+        .merge(preset_routes())
+        .merge(schedule_routes())
+        .merge(reset_routes())
+        .merge(live_routes())
+        .merge(event_routes())
+        .merge(metrics_routes())
+        .merge(panel_routes())
         .with_state(state)
+        .merge(peg_routes(pegs))
+        .merge(balls_routes(balls))
+        .merge(query_routes(query))
+        .merge(sim_routes(sim))
 }
 
 // Used by tests, not actually dead code.
 #[allow(dead_code)]
 pub fn build_router_for_test(tx: mpsc::Sender<PhysicsTuning>, mirror: Arc<Mutex<PhysicsTuning>>) -> Router {
-    build_router(tx, mirror)
+    build_router_with_state(AppState::new(tx, mirror))
+}
+
+/// Every route, behind the token auth and CORS layers `http` asks for.
+pub fn build_router_with_http(state: AppState, http: &HttpConfig) -> Router {
+    http.layer(build_router_with_state(state))
 }
 
 // Not a Resource; keep it plain to avoid Sync bound. We'll store it in a global once via insert_non_send_resource if needed.
@@ -465,55 +691,15 @@ pub fn apply_tuning_updates_system(rx: bevy::prelude::NonSend<TuningRx>, mut tun
 mod tests {
     use super::*;
 
-    fn sample_physics() -> PhysicsTuning {
-        PhysicsTuning {
-            rel_vel_min: 0.15,
-            rel_vel_max: 360.0,
-            break_force_threshold: 360.0,
-            energy_transfer_enabled: true,
-            energy_share_diff_threshold: 100,
-            energy_share_friendly_rate: 0.5,
-            energy_share_parent_not_friendly_child_friendly_rate: 0.75,
-            energy_share_parent_friendly_child_not_friendly_rate: 0.25,
-            energy_share_hostile_rand_min: 0.5,
-            energy_share_hostile_rand_max: 0.9,
-            bite_enabled: true,
-            bite_size_scale: 1.0,
-            genome_bite_size_min: 0,
-            genome_bite_size_max: 400,
-            genome_energy_share_min: 0.25,
-            genome_energy_share_max: 0.75,
-            genome_friendly_distance_min: 0.15,
-            genome_friendly_distance_max: 1.0,
-            genome_friendly_scent_range: 1.0,
-            genome_max_age_min: 90,
-            genome_max_age_max: 120,
-            genome_reproduction_rate_min: 0.011875,
-            genome_reproduction_rate_max: 0.0125,
-            genome_safe_reproduction_points_min: 0,
-            genome_safe_reproduction_points_max: 1000,
-            survival_cost_per_tick: 1,
-            show_collision_labels: false,
-            collision_label_force_min: 2.0,
-            show_break_labels: false,
-            break_label_impulse_min: 20.0,
-            show_age_labels: false,
-            age_label_min: 0.0,
-            age_label_max: f32::MAX,
-            show_energy_labels: false,
-            energy_label_min: 0.0,
-            energy_label_max: f32::MAX,
-        }
-    }
-
     #[test]
     fn conversion_roundtrip() {
-        let internal = sample_physics();
+        let internal = PhysicsTuning::default();
         let api: ApiTuning = (&internal).into();
         let back: PhysicsTuning = api.into();
         assert_eq!(internal.rel_vel_min, back.rel_vel_min);
         assert_eq!(internal.rel_vel_max, back.rel_vel_max);
         assert_eq!(internal.break_force_threshold, back.break_force_threshold);
+        assert_eq!(internal.joint_min_strength, back.joint_min_strength);
         assert_eq!(internal.energy_transfer_enabled, back.energy_transfer_enabled);
         assert_eq!(internal.energy_share_diff_threshold, back.energy_share_diff_threshold);
         assert_eq!(internal.energy_share_friendly_rate, back.energy_share_friendly_rate);
@@ -535,6 +721,7 @@ mod tests {
                 stick_range: Some(ApiStickRangeUpdate { rel_vel_min: Some(1.23), rel_vel_max: None }),
                 break_threshold: Some(42.0),
                 contact_force_performance_cutoff_fudge_factor: Some(0.00001),
+                fatigue: Some(ApiJointFatigueUpdate { joint_min_strength: Some(0.2), ..Default::default() }),
             }),
             labels: Some(ApiLabelsUpdate {
                 collision: Some(ApiCollisionLabelsUpdate { show_collision_labels: Some(true), collision_label_force_min: Some(3.3) }),
@@ -551,6 +738,7 @@ mod tests {
     // Integration-style test lives in tests/ for nameability
 
         assert_eq!(internal.break_force_threshold, 42.0);
+        assert_eq!(internal.joint_min_strength, 0.2);
         assert!(internal.show_collision_labels);
         assert_eq!(internal.collision_label_force_min, 3.3);
         assert!(internal.show_age_labels);
@@ -558,6 +746,73 @@ mod tests {
         assert_eq!(internal.energy_label_max, 900.0);
         assert!(internal.show_energy_labels);
     }
-}
 
+    #[test]
+    fn validate_reports_field_paths() {
+        assert_eq!(PhysicsTuning::default().validate(), Ok(()));
+
+        let bad = PhysicsTuning {
+            genome_max_age_min: 120,
+            genome_max_age_max: 90,
+            energy_share_friendly_rate: -0.5,
+            bite_size_scale: f32::NAN,
+            genome_reproduction_rate_max: f32::MAX,
+            ..PhysicsTuning::default()
+        };
+        let fields: Vec<String> = bad.validate().unwrap_err().into_iter().map(|v| v.field).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "energy_share.energy_share_friendly_rate",
+                "bite.bite_size_scale",
+                "reproduction.genome_reproduction_rate_range.genome_reproduction_rate_max",
+                "max_age.genome_max_age_range.genome_max_age_min",
+            ]
+        );
+        // Equal bounds would make gen_range panic
+        assert!(PhysicsTuning { genome_bite_size_min: 400, ..PhysicsTuning::default() }.validate().is_err());
+    }
+
+    // (JSON path, minimum, maximum) of every float the schema gives a range; integers only carry
+    // the minimum schemars adds for unsigned types
+    fn schema_ranges(node: &Value, defs: &Map<String, Value>, path: &str, out: &mut Vec<(String, Option<f64>, Option<f64>)>) {
+        if let Some(def) = schema_ref(node).and_then(|name| defs.get(&name)) {
+            return schema_ranges(def, defs, path, out);
+        }
+        if let Some(Value::Object(props)) = node.get("properties") {
+            for (field, prop) in props {
+                let path = if path.is_empty() { field.clone() } else { format!("{path}.{field}") };
+                schema_ranges(prop, defs, &path, out);
+            }
+        } else if node.get("type").and_then(Value::as_str) == Some("number") && (node.get("minimum").is_some() || node.get("maximum").is_some()) {
+            out.push((path.to_string(), node.get("minimum").and_then(Value::as_f64), node.get("maximum").and_then(Value::as_f64)));
+        }
+    }
 
+    #[test]
+    fn schema_ranges_match_validate() {
+        let schema = tuning_schema();
+        let defs = schema["definitions"].as_object().cloned().unwrap_or_default();
+        let mut ranges = Vec::new();
+        schema_ranges(&schema, &defs, "", &mut ranges);
+        assert!(ranges.len() > 20, "only {} ranged fields", ranges.len());
+
+        // Whether validate() rejects `value` at `path` for being out of range (other fields stay at their defaults)
+        let out_of_range = |path: &str, value: f32| {
+            let mut api = serde_json::to_value(ApiTuning::from(&PhysicsTuning::default())).unwrap();
+            *api.pointer_mut(&format!("/{}", path.replace('.', "/"))).unwrap() = Value::from(value);
+            let tuning = PhysicsTuning::from(serde_json::from_value::<ApiTuning>(api).unwrap());
+            tuning.validate().err().unwrap_or_default().iter().any(|v| v.field == path && v.reason.starts_with("must be between"))
+        };
+        for (path, min, max) in ranges {
+            if let Some(min) = min.map(|m| m as f32) {
+                assert!(!out_of_range(&path, min), "{path}: schema minimum {min} is rejected");
+                assert!(out_of_range(&path, min.next_down()), "{path}: accepts values below the schema minimum {min}");
+            }
+            if let Some(max) = max.map(|m| m as f32) {
+                assert!(!out_of_range(&path, max), "{path}: schema maximum {max} is rejected");
+                assert!(out_of_range(&path, max.next_up()), "{path}: accepts values above the schema maximum {max}");
+            }
+        }
+    }
+}
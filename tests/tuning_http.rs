@@ -1,53 +1,41 @@
 use axum::body::Body;
 use axum::http::{Request, StatusCode};
 use tower::ServiceExt;
+use bevy::prelude::Vec2;
 
-use live_whirl::tuning::{build_router_for_test, PhysicsTuning};
+use live_whirl::balls::{BallCommand, BallEdit, BallInfo, BallsApi, BallsSnapshot, GenomeInfo};
+use live_whirl::event_log::{EventFeed, EventLogFile, EventRecord};
+use live_whirl::events::{BallDied, DeathCause, SimEvent};
+use live_whirl::http::HttpConfig;
+use live_whirl::metrics::{Metrics, Summary, WorldMetrics};
+use live_whirl::presets::PresetStore;
+use live_whirl::query::{QueryApi, RegionResult, RegionShape, RegionStats};
+use live_whirl::sim::{ResetSpec, SimApi, SimCommand};
+use live_whirl::tuning::{build_router_for_test, build_router_with_http, build_router_with_state, AppState, PhysicsTuning};
 use std::sync::{Arc, Mutex};
 
-#[tokio::test]
-async fn http_get_and_patch_partial() {
+// Default tuning, nothing listening for tuning changes, world routes unattached.
+fn test_state() -> AppState {
     let (tx, _rx) = std::sync::mpsc::channel::<PhysicsTuning>();
-    let mirror = Arc::new(Mutex::new(PhysicsTuning {
-        rel_vel_min: 0.15,
-        rel_vel_max: 360.0,
-        break_force_threshold: 360.0,
-        energy_transfer_enabled: true,
-        energy_share_diff_threshold: 100,
-        energy_share_friendly_rate: 0.5,
-        energy_share_parent_not_friendly_child_friendly_rate: 0.75,
-        energy_share_parent_friendly_child_not_friendly_rate: 0.25,
-        energy_share_hostile_rand_min: 0.5,
-        energy_share_hostile_rand_max: 0.9,
-        bite_enabled: true,
-        bite_size_scale: 1.0,
-        genome_bite_size_min: 0,
-        genome_bite_size_max: 400,
-        genome_energy_share_min: 0.25,
-        genome_energy_share_max: 0.75,
-        genome_friendly_distance_min: 0.15,
-        genome_friendly_distance_max: 1.0,
-        genome_friendly_scent_range: 1.0,
-        genome_max_age_min: 90,
-        genome_max_age_max: 120,
-        genome_reproduction_rate_min: 0.011875,
-        genome_reproduction_rate_max: 0.0125,
-        genome_safe_reproduction_points_min: 0,
-        genome_safe_reproduction_points_max: 1000,
-        survival_cost_per_tick: 1,
-        show_collision_labels: false,
-        collision_label_force_min: 2.0,
-        show_break_labels: false,
-        break_label_impulse_min: 20.0,
-        show_age_labels: false,
-        age_label_min: 0.0,
-        age_label_max: f32::MAX,
-        show_energy_labels: false,
-        energy_label_min: 0.0,
-        energy_label_max: f32::MAX,
-    }));
+    AppState::new(tx, Arc::new(Mutex::new(PhysicsTuning::default())))
+}
 
-    let app = build_router_for_test(tx, mirror.clone());
+fn sample_genome() -> GenomeInfo {
+    GenomeInfo {
+        max_age: 100,
+        relative_reproduction_rate: 0.01,
+        bite_size: 50,
+        life_points_safe_to_reproduce: 10,
+        energy_share_with_children: 0.5,
+        friendly_scent: [0.0, 0.0],
+        friendly_distance: 0.2,
+    }
+}
+
+#[tokio::test]
+async fn http_get_and_patch_partial() {
+    let state = test_state();
+    let app = build_router_with_state(state.clone());
 
     let resp = app.clone().oneshot(Request::builder().uri("/tuning").body(Body::empty()).unwrap()).await.unwrap();
     assert_eq!(resp.status(), StatusCode::OK);
@@ -59,8 +47,483 @@ async fn http_get_and_patch_partial() {
     let resp = app.clone().oneshot(Request::builder().method("PATCH").uri("/tuning").header("content-type", "application/json").body(Body::from(payload.to_string())).unwrap()).await.unwrap();
     assert_eq!(resp.status(), StatusCode::OK);
 
+    let tuning = state.current_tuning();
+    assert_eq!(tuning.rel_vel_min, 1.11);
+    assert!(tuning.show_energy_labels);
+}
+
+#[tokio::test]
+async fn http_patch_rejects_invalid_ranges() {
+    let (tx, rx) = std::sync::mpsc::channel::<PhysicsTuning>();
+    let mirror = Arc::new(Mutex::new(PhysicsTuning::default()));
+    let app = build_router_for_test(tx, mirror.clone());
+
+    // Inverted genome range plus a negative rate
+    let payload = serde_json::json!({
+        "max_age": { "genome_max_age_range": { "genome_max_age_min": 500 } },
+        "energy_share": { "energy_share_friendly_rate": -1.0 }
+    });
+    let resp = app.oneshot(Request::builder().method("PATCH").uri("/tuning").header("content-type", "application/json").body(Body::from(payload.to_string())).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let err: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let fields: Vec<&str> = err["violations"].as_array().unwrap().iter().map(|v| v["field"].as_str().unwrap()).collect();
+    assert!(fields.contains(&"max_age.genome_max_age_range.genome_max_age_min"));
+    assert!(fields.contains(&"energy_share.energy_share_friendly_rate"));
+
+    // Nothing applied, nothing sent to the world
     let guard = mirror.lock().unwrap();
-    assert_eq!(guard.rel_vel_min, 1.11);
-    assert!(guard.show_energy_labels);
+    assert_eq!(guard.genome_max_age_min, 90);
+    assert_eq!(guard.energy_share_friendly_rate, 0.5);
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn http_presets_save_apply_delete() {
+    let dir = std::env::temp_dir().join(format!("live-whirl-presets-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (tx, rx) = std::sync::mpsc::channel::<PhysicsTuning>();
+    let mirror = Arc::new(Mutex::new(PhysicsTuning::default()));
+    let app = build_router_with_state(AppState::new(tx, mirror.clone()).with_presets(PresetStore::new(&dir)));
+    let send = |method: &str, uri: &str| Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+
+    let resp = app.clone().oneshot(send("PUT", "/presets/calm")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(dir.join("calm.json").exists());
+
+    let resp = app.clone().oneshot(send("GET", "/presets")).await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let names: Vec<String> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(names, vec!["calm".to_string()]);
+
+    // Drift away from the preset, then apply it back
+    mirror.lock().unwrap().survival_cost_per_tick = 7;
+    let resp = app.clone().oneshot(send("POST", "/presets/calm/apply")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(mirror.lock().unwrap().survival_cost_per_tick, 1);
+    assert_eq!(rx.try_recv().unwrap().survival_cost_per_tick, 1);
+
+    let resp = app.clone().oneshot(send("GET", "/presets/..%2Fescape")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+    let resp = app.clone().oneshot(send("DELETE", "/presets/calm")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    let resp = app.oneshot(send("GET", "/presets/calm")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn http_tuning_history_undo_redo() {
+    let state = test_state();
+    let app = build_router_with_state(state.clone());
+    let post = |uri: &str| Request::builder().method("POST").uri(uri).body(Body::empty()).unwrap();
+
+    let payload = serde_json::json!({ "note": "harsher winter", "max_age": { "survival_cost_per_tick": 5 } });
+    let resp = app.clone().oneshot(Request::builder().method("PATCH").uri("/tuning").header("content-type", "application/json").body(Body::from(payload.to_string())).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let resp = app.clone().oneshot(post("/tuning/undo")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(state.current_tuning().survival_cost_per_tick, 1);
+
+    let resp = app.clone().oneshot(post("/tuning/redo")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(state.current_tuning().survival_cost_per_tick, 5);
+
+    let resp = app.clone().oneshot(post("/tuning/redo")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app.oneshot(Request::builder().uri("/tuning/history").body(Body::empty()).unwrap()).await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let history: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let changes = history["changes"].as_array().unwrap();
+    let sources: Vec<&str> = changes.iter().map(|c| c["source"].as_str().unwrap()).collect();
+    assert_eq!(sources, vec!["patch", "undo 1", "redo 1"]);
+    assert_eq!(changes[0]["note"], "harsher winter");
+    assert_eq!(changes[0]["diff"][0]["field"], "survival_cost_per_tick");
+    assert_eq!(changes[0]["diff"][0]["before"], 1);
+    assert_eq!(history["can_undo"], true);
+}
+
+#[tokio::test]
+async fn http_schedule_add_list_cancel() {
+    let state = test_state();
+    let app = build_router_with_state(state.clone());
+    let json = |method: &str, uri: &str, body: serde_json::Value| {
+        Request::builder().method(method).uri(uri).header("content-type", "application/json").body(Body::from(body.to_string())).unwrap()
+    };
+
+    let ramp = serde_json::json!({ "at": { "seconds": 30.0 }, "ramp_secs": 60.0, "update": { "max_age": { "survival_cost_per_tick": 4 } } });
+    let resp = app.clone().oneshot(json("POST", "/tuning/schedule", ramp)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // Targets are validated up front
+    let bad = serde_json::json!({ "at": { "frame": 10 }, "update": { "bite": { "bite_size_scale": -1.0 } } });
+    let resp = app.clone().oneshot(json("POST", "/tuning/schedule", bad)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app.clone().oneshot(Request::builder().uri("/tuning/schedule").body(Body::empty()).unwrap()).await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let list: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(list.as_array().unwrap().len(), 1);
+    assert_eq!(list[0]["at"]["seconds"], 30.0);
+
+    let resp = app.clone().oneshot(Request::builder().method("DELETE").uri("/tuning/schedule/1").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.oneshot(Request::builder().method("DELETE").uri("/tuning/schedule/1").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+    // Nothing fires without the world ticking
+    assert_eq!(state.current_tuning().survival_cost_per_tick, 1);
+}
+
+#[tokio::test]
+async fn http_live_stream_pushes_tuning() {
+    use futures_util::StreamExt;
+
+    let app = build_router_with_state(test_state());
+
+    let resp = app.clone().oneshot(Request::builder().uri("/live").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let mut body = resp.into_body().into_data_stream();
+
+    // The current tuning arrives on connect
+    let first = String::from_utf8(body.next().await.unwrap().unwrap().to_vec()).unwrap();
+    assert!(first.starts_with("event: tuning\n"), "{first}");
+
+    // Then each accepted change
+    let payload = serde_json::json!({ "max_age": { "survival_cost_per_tick": 9 } });
+    app.oneshot(Request::builder().method("PATCH").uri("/tuning").header("content-type", "application/json").body(Body::from(payload.to_string())).unwrap()).await.unwrap();
+    let next = String::from_utf8(body.next().await.unwrap().unwrap().to_vec()).unwrap();
+    assert!(next.contains("\"survival_cost_per_tick\":9"), "{next}");
+}
+
+#[tokio::test]
+async fn http_tuning_schema_has_ranges_and_defaults() {
+    let app = build_router_with_state(test_state());
+
+    let resp = app.oneshot(Request::builder().uri("/tuning/schema").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let schema: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+    let defs = &schema["definitions"];
+    let friendly = &defs["ApiEnergyShare"]["properties"]["energy_share_friendly_rate"];
+    assert_eq!(friendly["minimum"], 0.0);
+    assert_eq!(friendly["maximum"], 1.0);
+    assert_eq!(friendly["default"], 0.5);
+    assert!(friendly["description"].as_str().is_some());
+    assert_eq!(defs["ApiGenomeMaxAgeRange"]["properties"]["genome_max_age_min"]["default"], 90);
+    // Renamed field keeps its wire name
+    assert!(defs["ApiLabels"]["properties"]["break"].is_object());
+}
+
+#[tokio::test]
+async fn http_panel_is_self_contained() {
+    let app = build_router_with_state(test_state());
+
+    let resp = app.oneshot(Request::builder().uri("/").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let html = std::str::from_utf8(&body).unwrap();
+    assert!(html.contains("/tuning/schema"));
+    assert!(!html.contains("http://") && !html.contains("https://"));
 }
 
+#[tokio::test]
+async fn http_token_guards_mutations_and_cors_allows_origin() {
+    let state = test_state();
+    let http = HttpConfig { token: Some("s3cret".to_string()), cors_origins: vec!["http://dash.local".to_string()], ..HttpConfig::default() };
+    let app = build_router_with_http(state, &http);
+    let patch = |auth: Option<&str>| {
+        let mut req = Request::builder().method("PATCH").uri("/tuning").header("content-type", "application/json");
+        if let Some(auth) = auth {
+            req = req.header("authorization", auth);
+        }
+        req.body(Body::from(r#"{"bite":{"bite_enabled":false}}"#)).unwrap()
+    };
+
+    // Reads stay open
+    let resp = app.clone().oneshot(Request::builder().uri("/tuning").header("origin", "http://dash.local").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["access-control-allow-origin"], "http://dash.local");
+
+    let resp = app.clone().oneshot(patch(None)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = app.clone().oneshot(patch(Some("Bearer wrong"))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+    let resp = app.clone().oneshot(patch(Some("Bearer s3cret"))).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    // Preflight needs no token
+    let preflight = Request::builder()
+        .method("OPTIONS")
+        .uri("/tuning")
+        .header("origin", "http://dash.local")
+        .header("access-control-request-method", "PATCH")
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.oneshot(preflight).await.unwrap();
+    assert!(resp.status().is_success());
+}
+
+#[tokio::test]
+async fn http_sim_step_needs_pause_and_speed_is_checked() {
+    let (sim, sim_rx, _mirror) = SimApi::channel();
+    let state = test_state().with_sim(sim);
+    let app = build_router_with_state(state);
+    let post = |uri: &str, body: &str| {
+        Request::builder().method("POST").uri(uri).header("content-type", "application/json").body(Body::from(body.to_string())).unwrap()
+    };
+
+    let resp = app.clone().oneshot(post("/sim/step?n=5", "")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+    let resp = app.clone().oneshot(post("/sim/pause", "")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.clone().oneshot(post("/sim/step?n=5", "")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status["paused"], true);
+    assert_eq!(status["pending_steps"], 5);
+
+    let resp = app.clone().oneshot(post("/sim/speed", r#"{"speed":0}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let resp = app.oneshot(post("/sim/speed", r#"{"speed":4}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let sent: Vec<SimCommand> = sim_rx.0.try_iter().collect();
+    assert_eq!(sent, vec![SimCommand::Pause, SimCommand::Step(5), SimCommand::Speed(4.0)]);
+}
+
+#[tokio::test]
+async fn http_balls_filter_paginate_and_lookup() {
+    let (balls_api, _balls_rx, mirror, _ids) = BallsApi::channel();
+    let genome = sample_genome();
+    let ball = |id: u64, age: u32, x: f32| BallInfo { id, x, y: 0.0, vx: 0.0, vy: 0.0, age, life_points: 500, genome, joints: Vec::new() };
+    *mirror.0.lock().unwrap() = BallsSnapshot { frame: 7, sim_seconds: 0.5, balls: vec![ball(1, 3, -10.0), ball(2, 40, 5.0), ball(3, 60, 20.0)] };
+    let app = build_router_with_state(test_state().with_balls(balls_api));
+    let get = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+    let resp = app.clone().oneshot(get("/balls?min_age=10&limit=1")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page["total"], 2);
+    assert_eq!(page["frame"], 7);
+    assert_eq!(page["balls"].as_array().unwrap().len(), 1);
+    assert_eq!(page["balls"][0]["id"], 2);
+
+    let resp = app.clone().oneshot(get("/balls?x_min=0&x_max=10")).await.unwrap();
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let page: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(page["total"], 1);
+
+    let resp = app.clone().oneshot(get("/balls/3")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let resp = app.oneshot(get("/balls/99")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn http_ball_commands_reach_the_world() {
+    let (balls_api, balls_rx, mirror, _ids) = BallsApi::channel();
+    let genome = sample_genome();
+    let ball = BallInfo { id: 1, x: 0.0, y: 0.0, vx: 0.0, vy: 0.0, age: 0, life_points: 500, genome, joints: Vec::new() };
+    *mirror.0.lock().unwrap() = BallsSnapshot { frame: 1, sim_seconds: 0.0, balls: vec![ball] };
+    let app = build_router_with_state(test_state().with_balls(balls_api));
+    let send = |method: &str, uri: &str, body: &str| {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let resp = app.clone().oneshot(send("POST", "/balls", r#"{"x":10,"y":20,"genome":{"bite_size":7}}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let spawned: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    let Ok(BallCommand::Spawn { id, spec }) = balls_rx.0.try_recv() else { panic!("expected a spawn command") };
+    assert_eq!(spawned["id"], id);
+    assert_eq!((spec.x, spec.y, spec.genome.bite_size), (10.0, 20.0, Some(7)));
+    let resp = app.clone().oneshot(send("POST", "/balls", r#"{"x":1e6,"y":20}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "outside the walls");
+
+    let resp = app.clone().oneshot(send("PATCH", "/balls/1/genome", r#"{"relative_reproduction_rate":2.0}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let resp = app.clone().oneshot(send("POST", "/balls/1/life", r#"{"delta":-100}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::ACCEPTED);
+    assert_eq!(balls_rx.0.try_recv().unwrap(), BallCommand::Edit { id: 1, edit: BallEdit::AdjustLife(-100) });
+    let resp = app.clone().oneshot(send("DELETE", "/balls/99", "")).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    assert!(balls_rx.0.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn http_region_query_is_answered_by_the_world() {
+    let (query_api, query_rx) = QueryApi::channel();
+    let genome = |scent: [f32; 2]| GenomeInfo { friendly_scent: scent, ..sample_genome() };
+    // Stand-in for the Bevy system: answer one request with two balls of the same scent and one stray
+    std::thread::spawn(move || {
+        let request = query_rx.0.recv().unwrap();
+        assert_eq!(request.shape, RegionShape::Circle { x: 0.0, y: 0.0, radius: 50.0 });
+        let ball = |id: u64, life_points: u32, scent: [f32; 2]| BallInfo { id, x: 0.0, y: 0.0, vx: 0.0, vy: 0.0, age: 10, life_points, genome: genome(scent), joints: Vec::new() };
+        let balls = vec![ball(1, 100, [0.5, 0.5]), ball(2, 200, [0.52, 0.48]), ball(3, 300, [-0.9, 0.1])];
+        let stats = RegionStats::of(&balls, &[], &[]);
+        request.reply.send(RegionResult { frame: 3, sim_seconds: 0.1, balls, pegs: Vec::new(), joints: Vec::new(), stats }).unwrap();
+    });
+    let app = build_router_with_state(test_state().with_query(query_api));
+    let post = |body: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/query/region")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let resp = app.clone().oneshot(post(r#"{"shape":"rect","x_min":5,"y_min":0,"x_max":1,"y_max":10}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let resp = app.oneshot(post(r#"{"shape":"circle","x":0,"y":0,"radius":50}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let result: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(result["stats"]["ball_count"], 3);
+    assert_eq!(result["stats"]["mean_life_points"], 200.0);
+    assert_eq!(result["stats"]["dominant_scent"]["scent"], serde_json::json!([0.5, 0.5]));
+    assert_eq!(result["stats"]["dominant_scent"]["count"], 2);
+}
+
+#[tokio::test]
+async fn http_sim_reset_reseeds_and_checks_its_inputs() {
+    let dir = std::env::temp_dir().join(format!("live-whirl-reset-presets-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let (sim, sim_rx, _mirror) = SimApi::channel();
+    let state = test_state().with_sim(sim).with_presets(PresetStore::new(&dir));
+    let app = build_router_with_state(state.clone());
+    let post = |body: &str| {
+        Request::builder().method("POST").uri("/sim/reset").header("content-type", "application/json").body(Body::from(body.to_string())).unwrap()
+    };
+
+    let resp = app.clone().oneshot(post(r#"{"preset":"missing"}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let resp = app.clone().oneshot(post(r#"{"arena":{"sticky_peg_fraction":0.8,"nutrient_peg_fraction":0.5}}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(sim_rx.0.try_recv().is_err());
+
+    let resp = app.clone().oneshot(post(r#"{"seed":7}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status["seed"], 7);
+    assert_eq!(status["resets"], 1);
+    assert_eq!(sim_rx.0.try_recv().unwrap(), SimCommand::Reset(ResetSpec { seed: 7, arena: None }));
+
+    // No body at all picks a seed
+    let resp = app.clone().oneshot(Request::builder().method("POST").uri("/sim/reset").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(matches!(sim_rx.0.try_recv().unwrap(), SimCommand::Reset(_)));
+
+    // A reset that cannot reach the world leaves the tuning as it was
+    let before = state.current_tuning();
+    let mut calm = before.clone();
+    calm.break_force_threshold += 1.0;
+    state.presets().save("calm", &calm).unwrap();
+    drop(sim_rx);
+    let resp = app.oneshot(post(r#"{"preset":"calm"}"#)).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(state.current_tuning().break_force_threshold, before.break_force_threshold);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn http_event_stream_and_file_share_ndjson_lines() {
+    use futures_util::StreamExt;
+
+    let feed = EventFeed::default();
+    let app = build_router_with_state(test_state().with_events(feed.clone()));
+
+    let resp = app.oneshot(Request::builder().uri("/events/stream").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "application/x-ndjson");
+    let mut body = resp.into_body().into_data_stream();
+
+    let record = EventRecord {
+        frame: 120,
+        sim_seconds: 2.0,
+        event: SimEvent::BallDied(BallDied { id: Some(4), cause: DeathCause::OldAge, position: Vec2::new(1.0, -2.0), age: 96 }),
+    };
+    feed.publish(record.clone());
+    let line = String::from_utf8(body.next().await.unwrap().unwrap().to_vec()).unwrap();
+    assert_eq!(line, record.to_line());
+    let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(parsed["type"], "ball_died");
+    assert_eq!(parsed["cause"], "old_age");
+    assert_eq!(parsed["frame"], 120);
+
+    assert_eq!(parsed["x"], 1.0);
+
+    let dir = std::env::temp_dir().join(format!("live-whirl-events-{}", std::process::id()));
+    let path = dir.join("run.events.ndjson");
+    let mut file = EventLogFile::create(&path).unwrap();
+    file.append([&record, &record]);
+    file.flush_if_due();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "", "buffered until the interval passes");
+    file.flush();
+    let text = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    let lines: Vec<EventRecord> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines, vec![record.clone(), record]);
+}
+
+#[tokio::test]
+async fn http_metrics_are_prometheus_text() {
+    let metrics = Metrics::default();
+    let app = build_router_with_state(test_state().with_metrics(metrics.clone()));
+
+    metrics.publish(WorldMetrics {
+        population: 3,
+        births: 7,
+        age: Summary::of(vec![10.0, 20.0, 30.0]),
+        step_seconds_sum: 0.5,
+        step_count: 100,
+        ..Default::default()
+    });
+    metrics.capture.captured(5);
+    metrics.capture.dropped(2);
+    metrics.capture.queued();
+    metrics.capture.queued();
+    metrics.capture.written();
+
+    let resp = app.oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert!(resp.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+    let body = axum::body::to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+    let text = String::from_utf8(body.to_vec()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    for expected in [
+        "# TYPE live_whirl_population gauge",
+        "live_whirl_population 3",
+        "live_whirl_births_total 7",
+        "live_whirl_ball_age_ticks_mean 20",
+        "live_whirl_ball_age_ticks{quantile=\"0.5\"} 20",
+        "live_whirl_ball_age_ticks{quantile=\"0.99\"} 30",
+        "live_whirl_ball_age_ticks_count 3",
+        "live_whirl_frames_captured_total 5",
+        "live_whirl_frames_dropped_total 2",
+        "live_whirl_ffmpeg_queue_depth 1",
+        "live_whirl_physics_step_seconds_count 100",
+    ] {
+        assert!(lines.contains(&expected), "missing {expected:?} in:\n{text}");
+    }
+    // No quantiles for an empty distribution
+    assert!(!text.contains("live_whirl_ball_life_points{quantile"));
+}
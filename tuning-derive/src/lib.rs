@@ -0,0 +1,471 @@
+//! `#[derive(TuningApi)]`: generates the nested HTTP shape of a flat tuning struct.
+//!
+//! From one flat struct with `#[tuning(...)]` attributes on every field this derives
+//! - the nested API structs (serde + schemars), one per declared group,
+//! - the matching all-`Option` update structs (`Default` = change nothing) with `apply_to`,
+//! - `From<&Flat> for Api` and `From<Api> for Flat`,
+//! - `Default for Flat`,
+//! - `Flat::check_ranges`, which hands every `range` field to a callback for validation.
+//!
+//! ```ignore
+//! #[derive(TuningApi)]
+//! #[tuning(api = "ApiTuning", update = "ApiTuningUpdate", doc = "Live tuning.")]
+//! #[tuning(group(path = "bite", name = "Bite", doc = "Biting."))]
+//! pub struct PhysicsTuning {
+//!     /// Multiplier on each genome's bite size
+//!     #[tuning(path = "bite.bite_size_scale", default = 1.0, range(min = 0.0, max = 1.0e9))]
+//!     pub bite_size_scale: f32,
+//! }
+//! ```
+//!
+//! Groups become `Api<name>` / `Api<name>Update`. The last path segment is the field's API name;
+//! a group whose segment is not a valid identifier sets `field = "..."` and is renamed on the wire.
+//! Doc comments on fields are copied onto the API fields, so they show up in the JSON Schema.
+//! `range` takes `min` and `max`, each a number or a string holding an expression
+//! (`min = "f32::MIN_POSITIVE"`), and is only allowed on `f32` fields. The same bounds feed the
+//! schema and `check_ranges`, so the two cannot drift apart.
+
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Attribute, Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit,
+    LitStr, MetaNameValue, Token, Type,
+};
+
+#[proc_macro_derive(TuningApi, attributes(tuning))]
+pub fn derive_tuning_api(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+struct Leaf {
+    ident: Ident,
+    ty: Type,
+    api_name: Ident,
+    path: LitStr,
+    docs: Vec<Attribute>,
+    range: Option<Range>,
+    default: Expr,
+}
+
+struct Range {
+    // As written, for schemars
+    tokens: TokenStream2,
+    min: Expr,
+    max: Expr,
+}
+
+// `min = ..., max = ...`; strings hold expressions, as schemars accepts them
+fn parse_range(tokens: TokenStream2, ty: &Type, span: Span) -> syn::Result<Range> {
+    if !matches!(ty, Type::Path(p) if p.path.is_ident("f32")) {
+        return Err(syn::Error::new(span, "range is only supported on f32 fields"));
+    }
+    let (mut min, mut max) = (None, None);
+    let pairs = syn::parse::Parser::parse2(Punctuated::<MetaNameValue, Token![,]>::parse_terminated, tokens.clone())?;
+    for pair in pairs {
+        let value = match pair.value {
+            Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => s.parse()?,
+            other => other,
+        };
+        if pair.path.is_ident("min") {
+            min = Some(value);
+        } else if pair.path.is_ident("max") {
+            max = Some(value);
+        } else {
+            return Err(syn::Error::new(pair.path.span(), "expected min or max"));
+        }
+    }
+    match (min, max) {
+        (Some(min), Some(max)) => Ok(Range { tokens, min, max }),
+        _ => Err(syn::Error::new(span, "range needs both min and max")),
+    }
+}
+
+enum Child {
+    Leaf(usize),
+    Group(String),
+}
+
+struct Group {
+    api: Ident,
+    update: Ident,
+    // Rust field name in the parent struct, and the wire name when it differs
+    field: Option<Ident>,
+    segment: String,
+    doc: Option<String>,
+    children: Vec<Child>,
+    span: Span,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let flat = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.span(), "TuningApi only supports structs"));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(input.span(), "TuningApi needs named fields"));
+    };
+
+    // Struct level: root names and the declared groups
+    let mut api: Option<LitStr> = None;
+    let mut update: Option<LitStr> = None;
+    let mut root_doc: Option<String> = None;
+    let mut groups: HashMap<String, Group> = HashMap::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("tuning")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("api") {
+                api = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("update") {
+                update = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("doc") {
+                root_doc = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("group") {
+                let span = meta.path.span();
+                let (mut path, mut name, mut field, mut doc) = (None, None, None, None);
+                meta.parse_nested_meta(|g| {
+                    let value: LitStr = g.value()?.parse()?;
+                    if g.path.is_ident("path") {
+                        path = Some(value.value());
+                    } else if g.path.is_ident("name") {
+                        name = Some(value);
+                    } else if g.path.is_ident("field") {
+                        field = Some(value.parse::<Ident>()?);
+                    } else if g.path.is_ident("doc") {
+                        doc = Some(value.value());
+                    } else {
+                        return Err(g.error("expected path, name, field or doc"));
+                    }
+                    Ok(())
+                })?;
+                let path = path.ok_or_else(|| syn::Error::new(span, "group needs a path"))?;
+                let name = name.ok_or_else(|| syn::Error::new(span, "group needs a name"))?;
+                let segment = path.rsplit('.').next().unwrap_or_default().to_string();
+                let field = match field {
+                    Some(f) => f,
+                    None => syn::parse_str::<Ident>(&segment)
+                        .map_err(|_| syn::Error::new(span, format!("group segment {segment:?} is not an identifier; set field = \"...\"")))?,
+                };
+                let group = Group {
+                    api: format_ident!("Api{}", name.value()),
+                    update: format_ident!("Api{}Update", name.value()),
+                    field: Some(field),
+                    segment,
+                    doc,
+                    children: Vec::new(),
+                    span,
+                };
+                if groups.insert(path.clone(), group).is_some() {
+                    return Err(syn::Error::new(span, format!("group {path:?} declared twice")));
+                }
+            } else {
+                return Err(meta.error("expected api, update, doc or group"));
+            }
+            Ok(())
+        })?;
+    }
+    let api = api.ok_or_else(|| syn::Error::new(input.span(), "missing #[tuning(api = \"...\")]"))?;
+    let update = update.ok_or_else(|| syn::Error::new(input.span(), "missing #[tuning(update = \"...\")]"))?;
+    groups.insert(
+        String::new(),
+        Group {
+            api: api.parse()?,
+            update: update.parse()?,
+            field: None,
+            segment: String::new(),
+            doc: root_doc,
+            children: Vec::new(),
+            span: input.span(),
+        },
+    );
+
+    // Field level: every field is a tunable with a path and a default
+    let mut leaves = Vec::new();
+    for field in &named.named {
+        let ident = field.ident.clone().expect("named field");
+        let (mut path, mut default, mut range) = (None::<LitStr>, None::<Expr>, None::<(TokenStream2, Span)>);
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("tuning")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("path") {
+                    path = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("range") {
+                    let span = meta.path.span();
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    range = Some((content.parse()?, span));
+                } else {
+                    return Err(meta.error("expected path, default or range"));
+                }
+                Ok(())
+            })?;
+        }
+        let path = path.ok_or_else(|| syn::Error::new(ident.span(), "tunable needs #[tuning(path = \"...\")]"))?;
+        let default = default.ok_or_else(|| syn::Error::new(ident.span(), "tunable needs #[tuning(default = ...)]"))?;
+        let segments: Vec<String> = path.value().split('.').map(str::to_string).collect();
+        let api_name: Ident = syn::parse_str(segments.last().map(String::as_str).unwrap_or_default())
+            .map_err(|_| syn::Error::new(path.span(), "last path segment must be an identifier"))?;
+
+        // Hook the field (and any group not yet seen) into the tree, in declaration order
+        for depth in 1..segments.len() {
+            let group_path = segments[..depth].join(".");
+            let parent_path = segments[..depth - 1].join(".");
+            if !groups.contains_key(&group_path) {
+                return Err(syn::Error::new(path.span(), format!("undeclared group {group_path:?}; add #[tuning(group(path = \"{group_path}\", name = \"...\"))]")));
+            }
+            let parent = groups.get_mut(&parent_path).expect("parent checked on the previous pass");
+            if !parent.children.iter().any(|c| matches!(c, Child::Group(p) if *p == group_path)) {
+                parent.children.push(Child::Group(group_path));
+            }
+        }
+        let owner = segments[..segments.len() - 1].join(".");
+        groups.get_mut(&owner).expect("owner declared").children.push(Child::Leaf(leaves.len()));
+
+        let range = range.map(|(tokens, span)| parse_range(tokens, &field.ty, span)).transpose()?;
+        leaves.push(Leaf {
+            ident,
+            ty: field.ty.clone(),
+            api_name,
+            path,
+            docs: field.attrs.iter().filter(|a| a.path().is_ident("doc")).cloned().collect(),
+            range,
+            default,
+        });
+    }
+    if let Some((path, g)) = groups.iter().find(|(path, g)| !path.is_empty() && g.children.is_empty()) {
+        return Err(syn::Error::new(g.span, format!("group {path:?} has no fields")));
+    }
+
+    let mut out = TokenStream2::new();
+    let mut paths: Vec<&String> = groups.keys().collect();
+    paths.sort();
+    for path in paths {
+        out.extend(group_structs(&groups[path], &groups, &leaves, flat));
+    }
+
+    let root = &groups[""];
+    let root_api = &root.api;
+    let to_api = to_api(root, &groups, &leaves);
+    let from_api: Vec<TokenStream2> = leaves
+        .iter()
+        .map(|l| {
+            let ident = &l.ident;
+            let access = access_path(l, &groups, &leaves);
+            quote! { #ident: api #(.#access)* }
+        })
+        .collect();
+    let defaults = leaves.iter().map(|l| {
+        let (ident, default) = (&l.ident, &l.default);
+        quote! { #ident: #default }
+    });
+    let range_checks = leaves.iter().filter_map(|l| {
+        let Range { min, max, .. } = l.range.as_ref()?;
+        let (ident, path) = (&l.ident, &l.path);
+        Some(quote! { within(#path, self.#ident, #min, #max); })
+    });
+
+    out.extend(quote! {
+        impl ::core::convert::From<&#flat> for #root_api {
+            fn from(t: &#flat) -> Self {
+                #to_api
+            }
+        }
+
+        impl ::core::convert::From<#root_api> for #flat {
+            fn from(api: #root_api) -> Self {
+                #flat { #(#from_api,)* }
+            }
+        }
+
+        impl ::core::default::Default for #flat {
+            fn default() -> Self {
+                #flat { #(#defaults,)* }
+            }
+        }
+
+        impl #flat {
+            /// Call `within(path, value, min, max)` for every field with a `range`, in declaration order.
+            pub fn check_ranges(&self, mut within: impl ::core::ops::FnMut(&'static str, f32, f32, f32)) {
+                #(#range_checks)*
+            }
+        }
+    });
+    Ok(out)
+}
+
+fn child_field(child: &Child, groups: &HashMap<String, Group>, leaves: &[Leaf]) -> (Ident, Option<String>) {
+    match child {
+        Child::Leaf(i) => (leaves[*i].api_name.clone(), None),
+        Child::Group(path) => {
+            let g = &groups[path];
+            let field = g.field.clone().expect("non-root group has a field");
+            let rename = (field != g.segment.as_str()).then(|| g.segment.clone());
+            (field, rename)
+        }
+    }
+}
+
+fn group_structs(group: &Group, groups: &HashMap<String, Group>, leaves: &[Leaf], flat: &Ident) -> TokenStream2 {
+    let (api, update) = (&group.api, &group.update);
+    let doc = group.doc.iter();
+    let mut api_fields = Vec::new();
+    let mut update_fields = Vec::new();
+    let mut applies = Vec::new();
+    for child in &group.children {
+        let (name, rename) = child_field(child, groups, leaves);
+        let rename = rename.map(|r| quote! { #[serde(rename = #r)] });
+        match child {
+            Child::Leaf(i) => {
+                let leaf = &leaves[*i];
+                let (ty, docs, ident) = (&leaf.ty, &leaf.docs, &leaf.ident);
+                let range = leaf.range.as_ref().map(|r| {
+                    let tokens = &r.tokens;
+                    quote! { #[schemars(range(#tokens))] }
+                });
+                api_fields.push(quote! { #(#docs)* #range #rename pub #name: #ty });
+                update_fields.push(quote! { #(#docs)* #rename pub #name: ::core::option::Option<#ty> });
+                applies.push(quote! { if let ::core::option::Option::Some(v) = self.#name { t.#ident = v; } });
+            }
+            Child::Group(path) => {
+                let g = &groups[path];
+                let (child_api, child_update) = (&g.api, &g.update);
+                api_fields.push(quote! { #rename pub #name: #child_api });
+                update_fields.push(quote! { #rename pub #name: ::core::option::Option<#child_update> });
+                applies.push(quote! { if let ::core::option::Option::Some(g) = self.#name { g.apply_to(t); } });
+            }
+        }
+    }
+    let update_doc = format!("Partial update of [`{api}`]; `None` leaves a value unchanged.");
+    quote! {
+        #(#[doc = #doc])*
+        #[derive(Debug, Clone, ::serde::Serialize, ::serde::Deserialize, ::schemars::JsonSchema)]
+        pub struct #api {
+            #(#api_fields,)*
+        }
+
+        #[doc = #update_doc]
+        #[derive(Debug, Clone, Default, ::serde::Serialize, ::serde::Deserialize)]
+        pub struct #update {
+            #(#update_fields,)*
+        }
+
+        impl #update {
+            /// Overwrite the fields that are `Some`.
+            pub fn apply_to(self, t: &mut #flat) {
+                #(#applies)*
+            }
+        }
+    }
+}
+
+fn to_api(group: &Group, groups: &HashMap<String, Group>, leaves: &[Leaf]) -> TokenStream2 {
+    let api = &group.api;
+    let fields = group.children.iter().map(|child| {
+        let (name, _) = child_field(child, groups, leaves);
+        match child {
+            Child::Leaf(i) => {
+                let ident = &leaves[*i].ident;
+                quote! { #name: ::core::clone::Clone::clone(&t.#ident) }
+            }
+            Child::Group(path) => {
+                let nested = to_api(&groups[path], groups, leaves);
+                quote! { #name: #nested }
+            }
+        }
+    });
+    quote! { #api { #(#fields,)* } }
+}
+
+// Field accesses from the root API struct down to a leaf, e.g. `labels.break_labels.show_break_labels`
+fn access_path(leaf: &Leaf, groups: &HashMap<String, Group>, leaves: &[Leaf]) -> Vec<Ident> {
+    fn find(path: &str, target: usize, groups: &HashMap<String, Group>, leaves: &[Leaf], out: &mut Vec<Ident>) -> bool {
+        for child in &groups[path].children {
+            let (name, _) = child_field(child, groups, leaves);
+            match child {
+                Child::Leaf(i) if *i == target => {
+                    out.push(name);
+                    return true;
+                }
+                Child::Leaf(_) => {}
+                Child::Group(sub) => {
+                    out.push(name);
+                    if find(sub, target, groups, leaves, out) {
+                        return true;
+                    }
+                    out.pop();
+                }
+            }
+        }
+        false
+    }
+    let target = leaves.iter().position(|l| l.ident == leaf.ident).expect("leaf is in the list");
+    let mut out = Vec::new();
+    find("", target, groups, leaves, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn expanded(input: DeriveInput) -> String {
+        expand(input).unwrap().to_string()
+    }
+
+    fn sample() -> DeriveInput {
+        parse_quote! {
+            #[tuning(api = "ApiT", update = "ApiTUpdate", doc = "Root.")]
+            #[tuning(group(path = "bite", name = "Bite", doc = "Biting."))]
+            #[tuning(group(path = "bite.break", name = "BreakLabels", field = "break_labels"))]
+            pub struct T {
+                /// Multiplier
+                #[tuning(path = "bite.scale", default = 1.0, range(min = "f32::MIN_POSITIVE", max = 2.0))]
+                pub scale: f32,
+                #[tuning(path = "bite.break.show", default = false)]
+                pub show: bool,
+                #[tuning(path = "count", default = 3)]
+                pub count: u32,
+            }
+        }
+    }
+
+    #[test]
+    fn generates_one_api_and_update_struct_per_group() {
+        let out = expanded(sample());
+        for name in ["ApiT", "ApiTUpdate", "ApiBite", "ApiBiteUpdate", "ApiBreakLabels", "ApiBreakLabelsUpdate"] {
+            assert!(out.contains(&format!("pub struct {name} ")), "missing {name}");
+        }
+        // Wire name differs from the Rust field
+        assert!(out.contains("# [serde (rename = \"break\")] pub break_labels : ApiBreakLabels"));
+        assert!(out.contains("pub count : :: core :: option :: Option < u32 >"));
+        assert!(out.contains("# [doc = r\" Multiplier\"]"));
+        assert!(out.contains("scale : 1.0"), "default");
+    }
+
+    #[test]
+    fn range_feeds_the_schema_and_check_ranges() {
+        let out = expanded(sample());
+        assert!(out.contains("# [schemars (range (min = \"f32::MIN_POSITIVE\" , max = 2.0))]"));
+        assert!(out.contains("within (\"bite.scale\" , self . scale , f32 :: MIN_POSITIVE , 2.0) ;"));
+        assert_eq!(out.matches("within (").count(), 1, "only ranged fields are checked");
+    }
+
+    #[test]
+    fn range_needs_both_bounds_on_an_f32() {
+        let err = |input: DeriveInput| expand(input).err().map(|e| e.to_string());
+        let half: DeriveInput = parse_quote! {
+            #[tuning(api = "A", update = "U")]
+            struct T { #[tuning(path = "x", default = 0.0, range(min = 0.0))] x: f32 }
+        };
+        assert_eq!(err(half).as_deref(), Some("range needs both min and max"));
+        let int: DeriveInput = parse_quote! {
+            #[tuning(api = "A", update = "U")]
+            struct T { #[tuning(path = "x", default = 0, range(min = 0, max = 1))] x: u32 }
+        };
+        assert_eq!(err(int).as_deref(), Some("range is only supported on f32 fields"));
+    }
+}
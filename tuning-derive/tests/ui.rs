@@ -0,0 +1,5 @@
+// Attribute mistakes must fail the build with a message that says what to fix.
+#[test]
+fn attribute_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,12 @@
+use tuning_derive::TuningApi;
+
+#[derive(TuningApi)]
+#[tuning(api = "ApiTuning", update = "ApiTuningUpdate")]
+#[tuning(group(path = "bite", name = "Bite"))]
+#[tuning(group(path = "bite", name = "Biting"))]
+pub struct Tuning {
+    #[tuning(path = "bite.bite_size_scale", default = 1.0)]
+    pub bite_size_scale: f32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use tuning_derive::TuningApi;
+
+#[derive(TuningApi)]
+#[tuning(api = "ApiTuning", update = "ApiTuningUpdate")]
+#[tuning(group(path = "bite", name = "Bite"))]
+pub struct Tuning {
+    #[tuning(path = "bite.bite_size_scale")]
+    pub bite_size_scale: f32,
+}
+
+fn main() {}
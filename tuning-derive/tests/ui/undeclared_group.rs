@@ -0,0 +1,10 @@
+use tuning_derive::TuningApi;
+
+#[derive(TuningApi)]
+#[tuning(api = "ApiTuning", update = "ApiTuningUpdate")]
+pub struct Tuning {
+    #[tuning(path = "bite.bite_size_scale", default = 1.0)]
+    pub bite_size_scale: f32,
+}
+
+fn main() {}